@@ -20,6 +20,19 @@ fn main() {
         return;
     }
 
+    // Allows type checking crates depending on the CUDA backend on machines without CUDA, nothing
+    // is built nor linked so any binary actually using the backend fails at link time
+    println!("cargo::rerun-if-env-changed=TFHE_CUDA_BACKEND_SKIP_BUILD");
+    if let Ok(val) = std::env::var("TFHE_CUDA_BACKEND_SKIP_BUILD") {
+        if val.parse::<u32>() == Ok(1) {
+            println!(
+                "cargo:warning=TFHE_CUDA_BACKEND_SKIP_BUILD is set, tfhe-cuda-backend is not \
+                built, linking code using it will fail"
+            );
+            return;
+        }
+    }
+
     println!("Build tfhe-cuda-backend");
     println!("cargo::rerun-if-changed=cuda/include");
     println!("cargo::rerun-if-changed=cuda/src");
@@ -41,19 +54,54 @@ fn main() {
         println!("cargo:rustc-link-search=native={}", dest.display());
         println!("cargo:rustc-link-lib=static=tfhe_cuda_backend");
 
-        // Try to find the cuda libs with pkg-config, default to the path used by the nvidia runfile
-        if pkg_config::Config::new()
+        // Paths from the environment (e.g. conda or custom prefix installs) come first
+        let mut cuda_lib_paths = Vec::new();
+        for var in ["CUDA_PATH", "CUDA_HOME"] {
+            println!("cargo::rerun-if-env-changed={var}");
+            if let Ok(cuda_path) = std::env::var(var) {
+                let lib_path = PathBuf::from(cuda_path).join("lib64");
+                println!("cargo:rustc-link-search=native={}", lib_path.display());
+                cuda_lib_paths.push(lib_path);
+            }
+        }
+
+        // Then try to find the cuda libs with pkg-config, default to the path used by the nvidia
+        // runfile
+        let found_with_pkg_config = pkg_config::Config::new()
             .atleast_version("10")
             .probe("cuda")
-            .is_err()
-        {
-            println!("cargo:rustc-link-search=native=/usr/local/cuda/lib64");
+            .is_ok();
+        if !found_with_pkg_config {
+            let lib_path = PathBuf::from("/usr/local/cuda/lib64");
+            println!("cargo:rustc-link-search=native={}", lib_path.display());
+            cuda_lib_paths.push(lib_path);
         }
         println!("cargo:rustc-link-lib=gomp");
         println!("cargo:rustc-link-lib=cudart");
-        println!("cargo:rustc-link-search=native=/usr/lib/x86_64-linux-gnu/");
+        let system_lib_path = PathBuf::from("/usr/lib/x86_64-linux-gnu/");
+        println!(
+            "cargo:rustc-link-search=native={}",
+            system_lib_path.display()
+        );
+        cuda_lib_paths.push(system_lib_path);
         println!("cargo:rustc-link-lib=stdc++");
 
+        if !found_with_pkg_config
+            && !cuda_lib_paths
+                .iter()
+                .any(|path| path.join("libcudart.so").exists())
+        {
+            let tried_paths = cuda_lib_paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!(
+                "cargo:warning=libcudart.so was not found by pkg-config nor in {tried_paths}, \
+                linking will likely fail. Set CUDA_PATH or CUDA_HOME to the CUDA install prefix."
+            );
+        }
+
         let header_path = "wrapper.h";
         let headers = vec![
             "wrapper.h",
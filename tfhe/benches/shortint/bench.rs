@@ -792,7 +792,8 @@ criterion_group!(
     casting,
     casting::pack_cast_64,
     casting::pack_cast,
-    casting::cast
+    casting::cast,
+    casting::cast_param_pairs
 );
 
 fn main() {
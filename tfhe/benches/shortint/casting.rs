@@ -2,7 +2,8 @@ use crate::utilities::{write_to_json, OperatorType};
 use criterion::Criterion;
 use rayon::prelude::*;
 use tfhe::shortint::parameters::{
-    PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+    ShortintKeySwitchingParameters, PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+    PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
 };
 use tfhe::shortint::prelude::*;
 
@@ -135,3 +136,120 @@ pub fn cast(c: &mut Criterion) {
         vec![],
     );
 }
+
+pub fn cast_param_pairs(c: &mut Criterion) {
+    let bench_name = "shortint::cast_param_pairs";
+    let mut bench_group = c.benchmark_group(bench_name);
+
+    // (source, destination) pairs covering the three cast_rshift orderings:
+    // upcast (keyswitch then PBS on the destination key),
+    // downcast (PBS on the source key then keyswitch and PBS on the destination key)
+    // and no shift (keyswitch and PBS on the destination key)
+    //
+    // The shipped casting parameters are used when they exist, otherwise the casting key uses the
+    // keyswitch parameters of the parameter set with the biggest message modulus, which keep
+    // enough bits for its encoding.
+    let param_pairs = [
+        (
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            Some((
+                PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+                "PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS",
+            )),
+        ),
+        (
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
+            None,
+        ),
+        (
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            None,
+        ),
+        (
+            PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            None,
+        ),
+        (
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            None,
+        ),
+    ];
+
+    for (src_params, dst_params, shipped_ks_param) in param_pairs {
+        let (client_key_1, server_key_1): (ClientKey, ServerKey) = gen_keys(src_params);
+        let (client_key_2, server_key_2): (ClientKey, ServerKey) = gen_keys(dst_params);
+
+        let src_full_modulus = src_params.message_modulus.0 * src_params.carry_modulus.0;
+        let dst_full_modulus = dst_params.message_modulus.0 * dst_params.carry_modulus.0;
+
+        let (ks_param, ks_param_name) = shipped_ks_param.map_or_else(
+            || {
+                let biggest_params = if dst_full_modulus > src_full_modulus {
+                    dst_params
+                } else {
+                    src_params
+                };
+
+                let ks_param = ShortintKeySwitchingParameters::new(
+                    biggest_params.ks_base_log,
+                    biggest_params.ks_level,
+                    dst_params.encryption_key_choice,
+                );
+
+                // Not a shipped parameter set, the name only describes the casting key
+                let ks_param_name = format!(
+                    "ks_{}_{}_to_{}_{}_base_log_{}_level_{}",
+                    src_params.message_modulus.0,
+                    src_params.carry_modulus.0,
+                    dst_params.message_modulus.0,
+                    dst_params.carry_modulus.0,
+                    ks_param.ks_base_log.0,
+                    ks_param.ks_level.0,
+                );
+
+                (ks_param, ks_param_name)
+            },
+            |(ks_param, ks_param_name)| (ks_param, ks_param_name.to_string()),
+        );
+
+        // The input of the casting key is a ciphertext under the big key of the source
+        let input_lwe_dimension = src_params
+            .glwe_dimension
+            .to_equivalent_lwe_dimension(src_params.polynomial_size);
+        if let Err(err) =
+            ks_param.is_suitable_for(input_lwe_dimension, src_full_modulus, dst_full_modulus)
+        {
+            panic!("{ks_param_name} is not suitable for the benchmarked cast: {err}");
+        }
+
+        let ksk = KeySwitchingKey::new(
+            (&client_key_1, Some(&server_key_1)),
+            (&client_key_2, &server_key_2),
+            ks_param,
+        );
+
+        let ct = client_key_1.encrypt(1);
+
+        let bench_id = format!("{bench_name}_{ks_param_name}");
+        bench_group.bench_function(&bench_id, |b| {
+            b.iter(|| {
+                let _ = ksk.cast(&ct);
+            });
+        });
+
+        write_to_json::<u64, _>(
+            &bench_id,
+            ks_param,
+            &ks_param_name,
+            "cast",
+            &OperatorType::Atomic,
+            0,
+            vec![],
+        );
+    }
+}
@@ -165,6 +165,122 @@ pub fn extract_lwe_sample_from_glwe_ciphertext<Scalar, InputCont, OutputCont>(
     }
 }
 
+/// Perform a sample extract on all coefficients from a [`GlweCiphertext`] in an output
+/// [`LweCiphertextList`], the ith [`LweCiphertext`] of the output list encrypts the ith
+/// coefficient of the input [`GlweCiphertext`].
+///
+/// This is equivalent to calling [`extract_lwe_sample_from_glwe_ciphertext`] for each
+/// [`MonomialDegree`] in `0..polynomial_size`, see
+/// [`par_extract_lwe_sample_from_glwe_ciphertext`] for a parallel variant.
+///
+/// # Formal definition
+///
+/// This operation is usually referred to as a _sample extract_ in the literature.
+///
+/// # Example
+///
+/// ```rust
+/// use tfhe::core_crypto::prelude::*;
+///
+/// // DISCLAIMER: these toy example parameters are not guaranteed to be secure or yield correct
+/// // computations
+/// // Define parameters for GlweCiphertext creation
+/// let glwe_size = GlweSize(2);
+/// let polynomial_size = PolynomialSize(1024);
+/// let glwe_noise_distribution =
+///     Gaussian::from_dispersion_parameter(StandardDev(0.00000000000000029403601535432533), 0.0);
+/// let ciphertext_modulus = CiphertextModulus::new_native();
+///
+/// // Create the PRNG
+/// let mut seeder = new_seeder();
+/// let seeder = seeder.as_mut();
+/// let mut encryption_generator =
+///     EncryptionRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed(), seeder);
+/// let mut secret_generator =
+///     SecretRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed());
+///
+/// // Create the GlweSecretKey
+/// let glwe_secret_key = allocate_and_generate_new_binary_glwe_secret_key(
+///     glwe_size.to_glwe_dimension(),
+///     polynomial_size,
+///     &mut secret_generator,
+/// );
+///
+/// // Create the plaintext
+/// let msg = 3u64;
+/// let encoded_msg = msg << 60;
+/// let plaintext_list = PlaintextList::new(encoded_msg, PlaintextCount(polynomial_size.0));
+///
+/// // Create a new GlweCiphertext
+/// let mut glwe = GlweCiphertext::new(0u64, glwe_size, polynomial_size, ciphertext_modulus);
+///
+/// encrypt_glwe_ciphertext(
+///     &glwe_secret_key,
+///     &mut glwe,
+///     &plaintext_list,
+///     glwe_noise_distribution,
+///     &mut encryption_generator,
+/// );
+///
+/// // Now we get the equivalent LweSecretKey from the GlweSecretKey
+/// let equivalent_lwe_sk = glwe_secret_key.clone().into_lwe_secret_key();
+///
+/// let mut extracted_samples = LweCiphertextList::new(
+///     0u64,
+///     equivalent_lwe_sk.lwe_dimension().to_lwe_size(),
+///     LweCiphertextCount(glwe.polynomial_size().0),
+///     ciphertext_modulus,
+/// );
+///
+/// extract_all_lwe_samples_from_glwe(&glwe, &mut extracted_samples);
+///
+/// let mut output_plaintext_list = PlaintextList::new(
+///     0u64,
+///     PlaintextCount(extracted_samples.lwe_ciphertext_count().0),
+/// );
+///
+/// decrypt_lwe_ciphertext_list(
+///     &equivalent_lwe_sk,
+///     &extracted_samples,
+///     &mut output_plaintext_list,
+/// );
+///
+/// // Round and remove encoding
+/// // First create a decomposer working on the high 4 bits corresponding to our encoding.
+/// let decomposer = SignedDecomposer::new(DecompositionBaseLog(4), DecompositionLevelCount(1));
+///
+/// output_plaintext_list
+///     .iter_mut()
+///     .for_each(|x| *x.0 = decomposer.closest_representable(*x.0) >> 60);
+///
+/// // We check we recover our msg stored in all slots of the GlweCiphertext
+/// assert!(output_plaintext_list.iter().all(|x| *x.0 == msg));
+/// ```
+pub fn extract_all_lwe_samples_from_glwe<Scalar, InputCont, OutputCont>(
+    input_glwe: &GlweCiphertext<InputCont>,
+    output_lwe_list: &mut LweCiphertextList<OutputCont>,
+) where
+    Scalar: UnsignedInteger,
+    InputCont: Container<Element = Scalar>,
+    OutputCont: ContainerMut<Element = Scalar>,
+{
+    assert!(
+        input_glwe.polynomial_size().0 <= output_lwe_list.lwe_ciphertext_count().0,
+        "The output LweCiphertextList does not have enough space ({:?}) \
+    to extract all input GlweCiphertext coefficients ({})",
+        output_lwe_list.lwe_ciphertext_count(),
+        input_glwe.polynomial_size().0
+    );
+
+    for (nth, mut output_lwe) in output_lwe_list
+        .iter_mut()
+        .take(input_glwe.polynomial_size().0)
+        .enumerate()
+    {
+        extract_lwe_sample_from_glwe_ciphertext(input_glwe, &mut output_lwe, MonomialDegree(nth));
+    }
+}
+
 /// Parallel variant of [`extract_lwe_sample_from_glwe_ciphertext`] performing a sample extract on
 /// all coefficients from a [`GlweCiphertext`] in an output [`LweCiphertextList`].
 ///
@@ -11,10 +11,13 @@ use crate::core_crypto::commons::math::random::{ActivatedRandomGenerator, Distri
 use crate::core_crypto::commons::parameters::*;
 use crate::core_crypto::commons::traits::*;
 use crate::core_crypto::entities::*;
+use rayon::prelude::*;
 
 /// Fill an [`LWE keyswitch key`](`LweKeyswitchKey`) with an actual keyswitching key constructed
 /// from an input and an output key [`LWE secret key`](`LweSecretKey`).
 ///
+/// Consider using [`par_generate_lwe_keyswitch_key`] for better key generation times.
+///
 /// ```rust
 /// use tfhe::core_crypto::prelude::*;
 ///
@@ -306,6 +309,197 @@ where
     new_lwe_keyswitch_key
 }
 
+/// Parallel variant of [`generate_lwe_keyswitch_key`], it is recommended to use this function for
+/// better key generation times when the input [`LweSecretKey`] is large.
+///
+/// The key elements are encrypted in parallel using rayon, the generated key is the same as the one
+/// produced by [`generate_lwe_keyswitch_key`] given the same [`EncryptionRandomGenerator`] state.
+///
+/// ```rust
+/// use tfhe::core_crypto::prelude::*;
+///
+/// // DISCLAIMER: these toy example parameters are not guaranteed to be secure or yield correct
+/// // computations
+/// // Define parameters for LweKeyswitchKey creation
+/// let input_lwe_dimension = LweDimension(742);
+/// let lwe_noise_distribution =
+///     Gaussian::from_dispersion_parameter(StandardDev(0.000007069849454709433), 0.0);
+/// let output_lwe_dimension = LweDimension(2048);
+/// let decomp_base_log = DecompositionBaseLog(3);
+/// let decomp_level_count = DecompositionLevelCount(5);
+/// let ciphertext_modulus = CiphertextModulus::new_native();
+///
+/// // Create the PRNG
+/// let mut seeder = new_seeder();
+/// let seeder = seeder.as_mut();
+/// let mut encryption_generator =
+///     EncryptionRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed(), seeder);
+/// let mut secret_generator =
+///     SecretRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed());
+///
+/// // Create the LweSecretKey
+/// let input_lwe_secret_key =
+///     allocate_and_generate_new_binary_lwe_secret_key(input_lwe_dimension, &mut secret_generator);
+/// let output_lwe_secret_key = allocate_and_generate_new_binary_lwe_secret_key(
+///     output_lwe_dimension,
+///     &mut secret_generator,
+/// );
+///
+/// let mut ksk = LweKeyswitchKey::new(
+///     0u64,
+///     decomp_base_log,
+///     decomp_level_count,
+///     input_lwe_dimension,
+///     output_lwe_dimension,
+///     ciphertext_modulus,
+/// );
+///
+/// par_generate_lwe_keyswitch_key(
+///     &input_lwe_secret_key,
+///     &output_lwe_secret_key,
+///     &mut ksk,
+///     lwe_noise_distribution,
+///     &mut encryption_generator,
+/// );
+///
+/// assert!(!ksk.as_ref().iter().all(|&x| x == 0));
+/// ```
+pub fn par_generate_lwe_keyswitch_key<
+    Scalar,
+    NoiseDistribution,
+    InputKeyCont,
+    OutputKeyCont,
+    KSKeyCont,
+    Gen,
+>(
+    input_lwe_sk: &LweSecretKey<InputKeyCont>,
+    output_lwe_sk: &LweSecretKey<OutputKeyCont>,
+    lwe_keyswitch_key: &mut LweKeyswitchKey<KSKeyCont>,
+    noise_distribution: NoiseDistribution,
+    generator: &mut EncryptionRandomGenerator<Gen>,
+) where
+    Scalar: Encryptable<Uniform, NoiseDistribution> + Sync + Send,
+    NoiseDistribution: Distribution + Sync,
+    InputKeyCont: Container<Element = Scalar>,
+    OutputKeyCont: Container<Element = Scalar> + Sync,
+    KSKeyCont: ContainerMut<Element = Scalar>,
+    Gen: ParallelByteRandomGenerator,
+{
+    assert!(
+        lwe_keyswitch_key.input_key_lwe_dimension() == input_lwe_sk.lwe_dimension(),
+        "The destination LweKeyswitchKey input LweDimension is not equal \
+    to the input LweSecretKey LweDimension. Destination: {:?}, input: {:?}",
+        lwe_keyswitch_key.input_key_lwe_dimension(),
+        input_lwe_sk.lwe_dimension()
+    );
+    assert!(
+        lwe_keyswitch_key.output_key_lwe_dimension() == output_lwe_sk.lwe_dimension(),
+        "The destination LweKeyswitchKey output LweDimension is not equal \
+    to the output LweSecretKey LweDimension. Destination: {:?}, output: {:?}",
+        lwe_keyswitch_key.output_key_lwe_dimension(),
+        output_lwe_sk.lwe_dimension()
+    );
+
+    let decomp_base_log = lwe_keyswitch_key.decomposition_base_log();
+    let decomp_level_count = lwe_keyswitch_key.decomposition_level_count();
+    let ciphertext_modulus = lwe_keyswitch_key.ciphertext_modulus();
+
+    let gen_iter = generator
+        .par_try_fork_from_config(
+            lwe_keyswitch_key.encryption_fork_config(Uniform, noise_distribution),
+        )
+        .unwrap();
+
+    lwe_keyswitch_key
+        .par_iter_mut()
+        .zip(input_lwe_sk.as_ref().par_iter())
+        .zip(gen_iter)
+        .for_each(
+            |((mut keyswitch_key_block, &input_key_element), mut generator)| {
+                // We fill the buffer with the powers of the key elements, see
+                // generate_lwe_keyswitch_key_native_mod_compatible and
+                // generate_lwe_keyswitch_key_other_mod
+                let decomposition_plaintexts: Vec<Scalar> = (1..=decomp_level_count.0)
+                    .map(DecompositionLevel)
+                    .rev()
+                    .map(|level| {
+                        if ciphertext_modulus.is_compatible_with_native_modulus() {
+                            DecompositionTerm::new(level, decomp_base_log, input_key_element)
+                                .to_recomposition_summand()
+                                .wrapping_div(
+                                    ciphertext_modulus.get_power_of_two_scaling_to_native_torus(),
+                                )
+                        } else {
+                            DecompositionTermNonNative::new(
+                                level,
+                                decomp_base_log,
+                                input_key_element,
+                                ciphertext_modulus,
+                            )
+                            .to_approximate_recomposition_summand()
+                        }
+                    })
+                    .collect();
+                let decomposition_plaintexts_buffer =
+                    PlaintextList::from_container(decomposition_plaintexts);
+
+                par_encrypt_lwe_ciphertext_list(
+                    output_lwe_sk,
+                    &mut keyswitch_key_block,
+                    &decomposition_plaintexts_buffer,
+                    noise_distribution,
+                    &mut generator,
+                );
+            },
+        );
+}
+
+/// Parallel variant of [`allocate_and_generate_new_lwe_keyswitch_key`], it is recommended to use
+/// this function for better key generation times when the input [`LweSecretKey`] is large.
+///
+/// See [`keyswitch_lwe_ciphertext`] for usage.
+pub fn par_allocate_and_generate_new_lwe_keyswitch_key<
+    Scalar,
+    NoiseDistribution,
+    InputKeyCont,
+    OutputKeyCont,
+    Gen,
+>(
+    input_lwe_sk: &LweSecretKey<InputKeyCont>,
+    output_lwe_sk: &LweSecretKey<OutputKeyCont>,
+    decomp_base_log: DecompositionBaseLog,
+    decomp_level_count: DecompositionLevelCount,
+    noise_distribution: NoiseDistribution,
+    ciphertext_modulus: CiphertextModulus<Scalar>,
+    generator: &mut EncryptionRandomGenerator<Gen>,
+) -> LweKeyswitchKeyOwned<Scalar>
+where
+    Scalar: Encryptable<Uniform, NoiseDistribution> + Sync + Send,
+    NoiseDistribution: Distribution + Sync,
+    InputKeyCont: Container<Element = Scalar>,
+    OutputKeyCont: Container<Element = Scalar> + Sync,
+    Gen: ParallelByteRandomGenerator,
+{
+    let mut new_lwe_keyswitch_key = LweKeyswitchKeyOwned::new(
+        Scalar::ZERO,
+        decomp_base_log,
+        decomp_level_count,
+        input_lwe_sk.lwe_dimension(),
+        output_lwe_sk.lwe_dimension(),
+        ciphertext_modulus,
+    );
+
+    par_generate_lwe_keyswitch_key(
+        input_lwe_sk,
+        output_lwe_sk,
+        &mut new_lwe_keyswitch_key,
+        noise_distribution,
+        generator,
+    );
+
+    new_lwe_keyswitch_key
+}
+
 /// Fill an [`LWE keyswitch key`](`SeededLweKeyswitchKey`) with an actual keyswitching key
 /// constructed from an input and an output key [`LWE secret key`](`LweSecretKey`).
 ///
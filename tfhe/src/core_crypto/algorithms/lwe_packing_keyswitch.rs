@@ -191,6 +191,12 @@ pub fn keyswitch_lwe_ciphertext_into_glwe_ciphertext<Scalar, KeyCont, InputCont,
 /// list`](`LweCiphertextList`) and pack the result in an output [`GLWE
 /// ciphertext`](`GlweCiphertext`).
 ///
+/// If the input list is empty the output is a trivial encryption of 0.
+///
+/// # Panics
+///
+/// Panics if the input list contains more ciphertexts than the output polynomial size.
+///
 /// ```rust
 /// use tfhe::core_crypto::prelude::*;
 ///
@@ -309,13 +315,193 @@ pub fn keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext<
     KeyCont: Container<Element = Scalar>,
     InputCont: Container<Element = Scalar>,
     OutputCont: ContainerMut<Element = Scalar>,
+{
+    check_packing_keyswitch_inputs(lwe_pksk, input_lwe_ciphertext, output_glwe_ciphertext);
+
+    keyswitch_and_pack_at_monomial_degrees_unchecked(
+        lwe_pksk,
+        input_lwe_ciphertext,
+        output_glwe_ciphertext,
+        MonomialDegree,
+    );
+}
+
+/// Apply a keyswitch on each [`LWE ciphertext`](`LweCiphertext`) of an input
+/// [`LWE ciphertext list`](`LweCiphertextList`) and pack the results in an output
+/// [`GLWE ciphertext`](`GlweCiphertext`), the i-th input ciphertext being put in the
+/// coefficients of degree `monomial_degrees[i]`.
+///
+/// All the other coefficients of the output encrypt 0.
+/// [`keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext`] is the special case where the
+/// ciphertexts are packed contiguously starting from the constant coefficient.
+///
+/// # Panics
+///
+/// Panics if the number of monomial degrees does not match the number of input ciphertexts or if a
+/// degree is greater or equal to the output polynomial size. Degrees appearing more than once are
+/// only detected in debug builds, in release builds the ciphertexts packed at the same degree are
+/// summed.
+///
+/// ```rust
+/// use tfhe::core_crypto::prelude::*;
+///
+/// // DISCLAIMER: these toy example parameters are not guaranteed to be secure or yield correct
+/// // computations
+/// // Define parameters for LweKeyswitchKey creation
+/// let input_lwe_dimension = LweDimension(742);
+/// let lwe_noise_distribution =
+///     Gaussian::from_dispersion_parameter(StandardDev(0.000007069849454709433), 0.0);
+/// let glwe_noise_distribution =
+///     Gaussian::from_dispersion_parameter(StandardDev(0.00000000000000029403601535432533), 0.0);
+/// let output_glwe_dimension = GlweDimension(1);
+/// let output_polynomial_size = PolynomialSize(2048);
+/// let decomp_base_log = DecompositionBaseLog(23);
+/// let decomp_level_count = DecompositionLevelCount(1);
+/// let ciphertext_modulus = CiphertextModulus::new_native();
+///
+/// // Create the PRNG
+/// let mut seeder = new_seeder();
+/// let seeder = seeder.as_mut();
+/// let mut encryption_generator =
+///     EncryptionRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed(), seeder);
+/// let mut secret_generator =
+///     SecretRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed());
+///
+/// // Create the LweSecretKey
+/// let input_lwe_secret_key =
+///     allocate_and_generate_new_binary_lwe_secret_key(input_lwe_dimension, &mut secret_generator);
+/// let output_glwe_secret_key = allocate_and_generate_new_binary_glwe_secret_key(
+///     output_glwe_dimension,
+///     output_polynomial_size,
+///     &mut secret_generator,
+/// );
+///
+/// let pksk = allocate_and_generate_new_lwe_packing_keyswitch_key(
+///     &input_lwe_secret_key,
+///     &output_glwe_secret_key,
+///     decomp_base_log,
+///     decomp_level_count,
+///     glwe_noise_distribution,
+///     ciphertext_modulus,
+///     &mut encryption_generator,
+/// );
+///
+/// // Pack the inputs in every other coefficient
+/// let input_count = output_glwe_secret_key.polynomial_size().0 / 2;
+/// let monomial_degrees: Vec<_> = (0..input_count).map(|i| MonomialDegree(2 * i)).collect();
+///
+/// // Create a new LweCiphertextList
+/// let mut input_lwe_list = LweCiphertextList::new(
+///     0u64,
+///     input_lwe_secret_key.lwe_dimension().to_lwe_size(),
+///     LweCiphertextCount(input_count),
+///     ciphertext_modulus,
+/// );
+///
+/// let mut input_plaintext_list = PlaintextList::new(0u64, PlaintextCount(input_count));
+///
+/// input_plaintext_list
+///     .iter_mut()
+///     .enumerate()
+///     .for_each(|(idx, dst)| *dst.0 = (idx as u64 % 16) << 60);
+///
+/// encrypt_lwe_ciphertext_list(
+///     &input_lwe_secret_key,
+///     &mut input_lwe_list,
+///     &input_plaintext_list,
+///     lwe_noise_distribution,
+///     &mut encryption_generator,
+/// );
+///
+/// let mut output_glwe = GlweCiphertext::new(
+///     0u64,
+///     output_glwe_secret_key.glwe_dimension().to_glwe_size(),
+///     output_glwe_secret_key.polynomial_size(),
+///     ciphertext_modulus,
+/// );
+///
+/// keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees(
+///     &pksk,
+///     &input_lwe_list,
+///     &mut output_glwe,
+///     &monomial_degrees,
+/// );
+///
+/// let mut decrypted_plaintext_list =
+///     PlaintextList::new(0u64, PlaintextCount(output_glwe.polynomial_size().0));
+///
+/// decrypt_glwe_ciphertext(
+///     &output_glwe_secret_key,
+///     &output_glwe,
+///     &mut decrypted_plaintext_list,
+/// );
+///
+/// // Round and remove encoding
+/// // First create a decomposer working on the high 4 bits corresponding to our encoding.
+/// let decomposer = SignedDecomposer::new(DecompositionBaseLog(4), DecompositionLevelCount(1));
+///
+/// decrypted_plaintext_list
+///     .iter_mut()
+///     .for_each(|x| *x.0 = decomposer.closest_representable(*x.0) >> 60);
+///
+/// // Check we recovered the original messages in the expected slots and zeros elsewhere
+/// for (idx, decrypted) in decrypted_plaintext_list.iter().enumerate() {
+///     let expected = if idx % 2 == 0 {
+///         *input_plaintext_list.get(idx / 2).0 >> 60
+///     } else {
+///         0
+///     };
+///     assert_eq!(*decrypted.0, expected);
+/// }
+/// ```
+pub fn keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees<
+    Scalar,
+    KeyCont,
+    InputCont,
+    OutputCont,
+>(
+    lwe_pksk: &LwePackingKeyswitchKey<KeyCont>,
+    input_lwe_ciphertext: &LweCiphertextList<InputCont>,
+    output_glwe_ciphertext: &mut GlweCiphertext<OutputCont>,
+    monomial_degrees: &[MonomialDegree],
+) where
+    Scalar: UnsignedInteger,
+    KeyCont: Container<Element = Scalar>,
+    InputCont: Container<Element = Scalar>,
+    OutputCont: ContainerMut<Element = Scalar>,
+{
+    check_packing_keyswitch_inputs(lwe_pksk, input_lwe_ciphertext, output_glwe_ciphertext);
+    check_monomial_degrees(
+        input_lwe_ciphertext.lwe_ciphertext_count(),
+        output_glwe_ciphertext.polynomial_size(),
+        monomial_degrees,
+    );
+
+    keyswitch_and_pack_at_monomial_degrees_unchecked(
+        lwe_pksk,
+        input_lwe_ciphertext,
+        output_glwe_ciphertext,
+        |idx| monomial_degrees[idx],
+    );
+}
+
+fn check_packing_keyswitch_inputs<Scalar, KeyCont, InputCont, OutputCont>(
+    lwe_pksk: &LwePackingKeyswitchKey<KeyCont>,
+    input_lwe_ciphertext_list: &LweCiphertextList<InputCont>,
+    output_glwe_ciphertext: &GlweCiphertext<OutputCont>,
+) where
+    Scalar: UnsignedInteger,
+    KeyCont: Container<Element = Scalar>,
+    InputCont: Container<Element = Scalar>,
+    OutputCont: Container<Element = Scalar>,
 {
     assert!(
-        lwe_pksk.input_key_lwe_dimension() == input_lwe_ciphertext.lwe_size().to_lwe_dimension(),
+        lwe_pksk.input_key_lwe_dimension()
+            == input_lwe_ciphertext_list.lwe_size().to_lwe_dimension(),
         "Mismatched input LweDimension. \
         LwePackingKeyswitchKey input LweDimension: {:?}, input LweCiphertext LweDimension {:?}.",
         lwe_pksk.input_key_lwe_dimension(),
-        input_lwe_ciphertext.lwe_size().to_lwe_dimension()
+        input_lwe_ciphertext_list.lwe_size().to_lwe_dimension()
     );
     assert!(
         lwe_pksk.output_key_glwe_dimension()
@@ -336,11 +522,11 @@ pub fn keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext<
     );
 
     assert!(
-        lwe_pksk.ciphertext_modulus() == input_lwe_ciphertext.ciphertext_modulus(),
+        lwe_pksk.ciphertext_modulus() == input_lwe_ciphertext_list.ciphertext_modulus(),
         "Mismatched CiphertextModulus. \
         LwePackingKeyswitchKey CiphertextModulus: {:?}, input LweCiphertext CiphertextModulus {:?}.",
         lwe_pksk.ciphertext_modulus(),
-        input_lwe_ciphertext.ciphertext_modulus()
+        input_lwe_ciphertext_list.ciphertext_modulus()
     );
     assert!(
         lwe_pksk.ciphertext_modulus() == output_glwe_ciphertext.ciphertext_modulus(),
@@ -351,15 +537,69 @@ pub fn keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext<
         output_glwe_ciphertext.ciphertext_modulus()
     );
     assert!(
-        input_lwe_ciphertext
+        input_lwe_ciphertext_list
             .ciphertext_modulus()
             .is_compatible_with_native_modulus(),
         "This operation currently only supports power of 2 moduli"
     );
 
     assert!(
-        input_lwe_ciphertext.lwe_ciphertext_count().0 <= output_glwe_ciphertext.polynomial_size().0
+        input_lwe_ciphertext_list.lwe_ciphertext_count().0
+            <= output_glwe_ciphertext.polynomial_size().0,
+        "Cannot pack more LweCiphertext ({:?}) than the output PolynomialSize ({:?}).",
+        input_lwe_ciphertext_list.lwe_ciphertext_count(),
+        output_glwe_ciphertext.polynomial_size()
+    );
+}
+
+fn check_monomial_degrees(
+    input_lwe_ciphertext_count: LweCiphertextCount,
+    output_polynomial_size: PolynomialSize,
+    monomial_degrees: &[MonomialDegree],
+) {
+    assert_eq!(
+        input_lwe_ciphertext_count.0,
+        monomial_degrees.len(),
+        "Mismatched number of input LweCiphertext ({:?}) and output MonomialDegree ({:?}).",
+        input_lwe_ciphertext_count,
+        monomial_degrees.len()
+    );
+
+    for degree in monomial_degrees {
+        assert!(
+            degree.0 < output_polynomial_size.0,
+            "MonomialDegree {:?} is out of bounds for the output PolynomialSize {:?}.",
+            degree,
+            output_polynomial_size
+        );
+    }
+
+    // Checking the degrees are distinct requires an allocation, only do it in debug builds
+    debug_assert!(
+        {
+            let mut sorted_degrees: Vec<_> =
+                monomial_degrees.iter().map(|degree| degree.0).collect();
+            sorted_degrees.sort_unstable();
+            sorted_degrees.windows(2).all(|pair| pair[0] != pair[1])
+        },
+        "A MonomialDegree is used more than once, \
+        LweCiphertext packed in the same slot would be summed."
     );
+}
+
+/// Pack the i-th input ciphertext at `monomial_degree(i)`, the inputs are expected to have been
+/// checked by the caller.
+fn keyswitch_and_pack_at_monomial_degrees_unchecked<Scalar, KeyCont, InputCont, OutputCont>(
+    lwe_pksk: &LwePackingKeyswitchKey<KeyCont>,
+    input_lwe_ciphertext_list: &LweCiphertextList<InputCont>,
+    output_glwe_ciphertext: &mut GlweCiphertext<OutputCont>,
+    monomial_degree: impl Fn(usize) -> MonomialDegree,
+) where
+    Scalar: UnsignedInteger,
+    KeyCont: Container<Element = Scalar>,
+    InputCont: Container<Element = Scalar>,
+    OutputCont: ContainerMut<Element = Scalar>,
+{
     output_glwe_ciphertext.as_mut().fill(Scalar::ZERO);
     let mut buffer = GlweCiphertext::new(
         Scalar::ZERO,
@@ -368,13 +608,14 @@ pub fn keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext<
         output_glwe_ciphertext.ciphertext_modulus(),
     );
     // for each ciphertext, call mono_key_switch
-    for (degree, input_ciphertext) in input_lwe_ciphertext.iter().enumerate() {
+    for (idx, input_ciphertext) in input_lwe_ciphertext_list.iter().enumerate() {
+        let degree = monomial_degree(idx);
         keyswitch_lwe_ciphertext_into_glwe_ciphertext(lwe_pksk, &input_ciphertext, &mut buffer);
         buffer
             .as_mut_polynomial_list()
             .iter_mut()
             .for_each(|mut poly| {
-                polynomial_wrapping_monic_monomial_mul_assign(&mut poly, MonomialDegree(degree));
+                polynomial_wrapping_monic_monomial_mul_assign(&mut poly, degree);
             });
         slice_wrapping_add_assign(output_glwe_ciphertext.as_mut(), buffer.as_ref());
     }
@@ -642,64 +883,347 @@ pub fn par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_with_thread
     InputCont: Container<Element = Scalar>,
     OutputCont: ContainerMut<Element = Scalar>,
 {
-    assert!(
-        lwe_pksk.input_key_lwe_dimension()
-            == input_lwe_ciphertext_list.lwe_size().to_lwe_dimension(),
-        "Mismatched input LweDimension. \
-        LwePackingKeyswitchKey input LweDimension: {:?}, input LweCiphertext LweDimension {:?}.",
-        lwe_pksk.input_key_lwe_dimension(),
-        input_lwe_ciphertext_list.lwe_size().to_lwe_dimension()
-    );
-    assert!(
-        lwe_pksk.output_key_glwe_dimension()
-            == output_glwe_ciphertext.glwe_size().to_glwe_dimension(),
-        "Mismatched output GlweDimension. \
-        LwePackingKeyswitchKey output GlweDimension: {:?}, \
-        output GlweCiphertext GlweDimension {:?}.",
-        lwe_pksk.output_key_glwe_dimension(),
-        output_glwe_ciphertext.glwe_size().to_glwe_dimension()
-    );
-    assert!(
-        lwe_pksk.output_key_polynomial_size() == output_glwe_ciphertext.polynomial_size(),
-        "Mismatched output PolynomialSize. \
-        LwePackingKeyswitchKey output PolynomialSize: {:?}, \
-        output GlweCiphertext PolynomialSize {:?}.",
-        lwe_pksk.output_key_polynomial_size(),
-        output_glwe_ciphertext.polynomial_size()
-    );
+    check_packing_keyswitch_inputs(lwe_pksk, input_lwe_ciphertext_list, output_glwe_ciphertext);
 
-    assert!(
-        lwe_pksk.ciphertext_modulus() == input_lwe_ciphertext_list.ciphertext_modulus(),
-        "Mismatched CiphertextModulus. \
-        LwePackingKeyswitchKey CiphertextModulus: {:?}, input LweCiphertext CiphertextModulus {:?}.",
-        lwe_pksk.ciphertext_modulus(),
-        input_lwe_ciphertext_list.ciphertext_modulus()
+    par_keyswitch_and_pack_at_monomial_degrees_unchecked(
+        lwe_pksk,
+        input_lwe_ciphertext_list,
+        output_glwe_ciphertext,
+        MonomialDegree,
+        thread_count,
     );
-    assert!(
-        lwe_pksk.ciphertext_modulus() == output_glwe_ciphertext.ciphertext_modulus(),
-        "Mismatched CiphertextModulus. \
-        LwePackingKeyswitchKey CiphertextModulus: {:?}, \
-        output LweCiphertext CiphertextModulus {:?}.",
-        lwe_pksk.ciphertext_modulus(),
-        output_glwe_ciphertext.ciphertext_modulus()
+}
+
+/// Parallel variant of
+/// [`keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees`].
+///
+/// This will use all threads available in the current rayon thread pool.
+///
+/// # Panics
+///
+/// See [`keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees`].
+///
+/// ```rust
+/// use tfhe::core_crypto::prelude::*;
+///
+/// // DISCLAIMER: these toy example parameters are not guaranteed to be secure or yield correct
+/// // computations
+/// // Define parameters for LweKeyswitchKey creation
+/// let input_lwe_dimension = LweDimension(742);
+/// let lwe_noise_distribution =
+///     Gaussian::from_dispersion_parameter(StandardDev(0.000007069849454709433), 0.0);
+/// let glwe_noise_distribution =
+///     Gaussian::from_dispersion_parameter(StandardDev(0.00000000000000029403601535432533), 0.0);
+/// let output_glwe_dimension = GlweDimension(1);
+/// let output_polynomial_size = PolynomialSize(2048);
+/// let decomp_base_log = DecompositionBaseLog(23);
+/// let decomp_level_count = DecompositionLevelCount(1);
+/// let ciphertext_modulus = CiphertextModulus::new_native();
+///
+/// // Create the PRNG
+/// let mut seeder = new_seeder();
+/// let seeder = seeder.as_mut();
+/// let mut encryption_generator =
+///     EncryptionRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed(), seeder);
+/// let mut secret_generator =
+///     SecretRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed());
+///
+/// // Create the LweSecretKey
+/// let input_lwe_secret_key =
+///     allocate_and_generate_new_binary_lwe_secret_key(input_lwe_dimension, &mut secret_generator);
+/// let output_glwe_secret_key = allocate_and_generate_new_binary_glwe_secret_key(
+///     output_glwe_dimension,
+///     output_polynomial_size,
+///     &mut secret_generator,
+/// );
+///
+/// let pksk = allocate_and_generate_new_lwe_packing_keyswitch_key(
+///     &input_lwe_secret_key,
+///     &output_glwe_secret_key,
+///     decomp_base_log,
+///     decomp_level_count,
+///     glwe_noise_distribution,
+///     ciphertext_modulus,
+///     &mut encryption_generator,
+/// );
+///
+/// // Pack the inputs in every other coefficient
+/// let input_count = output_glwe_secret_key.polynomial_size().0 / 2;
+/// let monomial_degrees: Vec<_> = (0..input_count).map(|i| MonomialDegree(2 * i)).collect();
+///
+/// // Create a new LweCiphertextList
+/// let mut input_lwe_list = LweCiphertextList::new(
+///     0u64,
+///     input_lwe_secret_key.lwe_dimension().to_lwe_size(),
+///     LweCiphertextCount(input_count),
+///     ciphertext_modulus,
+/// );
+///
+/// let mut input_plaintext_list = PlaintextList::new(0u64, PlaintextCount(input_count));
+///
+/// input_plaintext_list
+///     .iter_mut()
+///     .enumerate()
+///     .for_each(|(idx, dst)| *dst.0 = (idx as u64 % 16) << 60);
+///
+/// encrypt_lwe_ciphertext_list(
+///     &input_lwe_secret_key,
+///     &mut input_lwe_list,
+///     &input_plaintext_list,
+///     lwe_noise_distribution,
+///     &mut encryption_generator,
+/// );
+///
+/// let mut output_glwe = GlweCiphertext::new(
+///     0u64,
+///     output_glwe_secret_key.glwe_dimension().to_glwe_size(),
+///     output_glwe_secret_key.polynomial_size(),
+///     ciphertext_modulus,
+/// );
+///
+/// // Use all threads available in the current rayon thread pool
+/// par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees(
+///     &pksk,
+///     &input_lwe_list,
+///     &mut output_glwe,
+///     &monomial_degrees,
+/// );
+///
+/// let mut decrypted_plaintext_list =
+///     PlaintextList::new(0u64, PlaintextCount(output_glwe.polynomial_size().0));
+///
+/// decrypt_glwe_ciphertext(
+///     &output_glwe_secret_key,
+///     &output_glwe,
+///     &mut decrypted_plaintext_list,
+/// );
+///
+/// // Round and remove encoding
+/// // First create a decomposer working on the high 4 bits corresponding to our encoding.
+/// let decomposer = SignedDecomposer::new(DecompositionBaseLog(4), DecompositionLevelCount(1));
+///
+/// decrypted_plaintext_list
+///     .iter_mut()
+///     .for_each(|x| *x.0 = decomposer.closest_representable(*x.0) >> 60);
+///
+/// // Check we recovered the original messages in the expected slots and zeros elsewhere
+/// for (idx, decrypted) in decrypted_plaintext_list.iter().enumerate() {
+///     let expected = if idx % 2 == 0 {
+///         *input_plaintext_list.get(idx / 2).0 >> 60
+///     } else {
+///         0
+///     };
+///     assert_eq!(*decrypted.0, expected);
+/// }
+/// ```
+pub fn par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees<
+    Scalar,
+    KeyCont,
+    InputCont,
+    OutputCont,
+>(
+    lwe_pksk: &LwePackingKeyswitchKey<KeyCont>,
+    input_lwe_ciphertext_list: &LweCiphertextList<InputCont>,
+    output_glwe_ciphertext: &mut GlweCiphertext<OutputCont>,
+    monomial_degrees: &[MonomialDegree],
+) where
+    Scalar: UnsignedInteger + Send + Sync,
+    KeyCont: Container<Element = Scalar> + Sync,
+    InputCont: Container<Element = Scalar>,
+    OutputCont: ContainerMut<Element = Scalar>,
+{
+    let thread_count = ThreadCount(rayon::current_num_threads());
+    par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees_with_thread_count(
+        lwe_pksk,
+        input_lwe_ciphertext_list,
+        output_glwe_ciphertext,
+        monomial_degrees,
+        thread_count,
     );
-    assert!(
-        input_lwe_ciphertext_list
-            .ciphertext_modulus()
-            .is_compatible_with_native_modulus(),
-        "This operation currently only supports power of 2 moduli"
+}
+
+/// Parallel variant of
+/// [`keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees`].
+///
+/// This will try to use `thread_count` threads for the computation, if this number is bigger than
+/// the available number of threads in the current rayon thread pool then only the number of
+/// available threads will be used. Note that `thread_count` cannot be 0.
+///
+/// # Panics
+///
+/// See [`keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees`].
+///
+/// ```rust
+/// use tfhe::core_crypto::prelude::*;
+///
+/// // DISCLAIMER: these toy example parameters are not guaranteed to be secure or yield correct
+/// // computations
+/// // Define parameters for LweKeyswitchKey creation
+/// let input_lwe_dimension = LweDimension(742);
+/// let lwe_noise_distribution =
+///     Gaussian::from_dispersion_parameter(StandardDev(0.000007069849454709433), 0.0);
+/// let glwe_noise_distribution =
+///     Gaussian::from_dispersion_parameter(StandardDev(0.00000000000000029403601535432533), 0.0);
+/// let output_glwe_dimension = GlweDimension(1);
+/// let output_polynomial_size = PolynomialSize(2048);
+/// let decomp_base_log = DecompositionBaseLog(23);
+/// let decomp_level_count = DecompositionLevelCount(1);
+/// let ciphertext_modulus = CiphertextModulus::new_native();
+///
+/// // Create the PRNG
+/// let mut seeder = new_seeder();
+/// let seeder = seeder.as_mut();
+/// let mut encryption_generator =
+///     EncryptionRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed(), seeder);
+/// let mut secret_generator =
+///     SecretRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed());
+///
+/// // Create the LweSecretKey
+/// let input_lwe_secret_key =
+///     allocate_and_generate_new_binary_lwe_secret_key(input_lwe_dimension, &mut secret_generator);
+/// let output_glwe_secret_key = allocate_and_generate_new_binary_glwe_secret_key(
+///     output_glwe_dimension,
+///     output_polynomial_size,
+///     &mut secret_generator,
+/// );
+///
+/// let pksk = allocate_and_generate_new_lwe_packing_keyswitch_key(
+///     &input_lwe_secret_key,
+///     &output_glwe_secret_key,
+///     decomp_base_log,
+///     decomp_level_count,
+///     glwe_noise_distribution,
+///     ciphertext_modulus,
+///     &mut encryption_generator,
+/// );
+///
+/// // Pack the inputs in every other coefficient
+/// let input_count = output_glwe_secret_key.polynomial_size().0 / 2;
+/// let monomial_degrees: Vec<_> = (0..input_count).map(|i| MonomialDegree(2 * i)).collect();
+///
+/// // Create a new LweCiphertextList
+/// let mut input_lwe_list = LweCiphertextList::new(
+///     0u64,
+///     input_lwe_secret_key.lwe_dimension().to_lwe_size(),
+///     LweCiphertextCount(input_count),
+///     ciphertext_modulus,
+/// );
+///
+/// let mut input_plaintext_list = PlaintextList::new(0u64, PlaintextCount(input_count));
+///
+/// input_plaintext_list
+///     .iter_mut()
+///     .enumerate()
+///     .for_each(|(idx, dst)| *dst.0 = (idx as u64 % 16) << 60);
+///
+/// encrypt_lwe_ciphertext_list(
+///     &input_lwe_secret_key,
+///     &mut input_lwe_list,
+///     &input_plaintext_list,
+///     lwe_noise_distribution,
+///     &mut encryption_generator,
+/// );
+///
+/// let mut output_glwe = GlweCiphertext::new(
+///     0u64,
+///     output_glwe_secret_key.glwe_dimension().to_glwe_size(),
+///     output_glwe_secret_key.polynomial_size(),
+///     ciphertext_modulus,
+/// );
+///
+/// // Try to use 4 threads for the keyswitch if enough are available
+/// // in the current rayon thread pool
+/// par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees_with_thread_count(
+///     &pksk,
+///     &input_lwe_list,
+///     &mut output_glwe,
+///     &monomial_degrees,
+///     ThreadCount(4),
+/// );
+///
+/// let mut decrypted_plaintext_list =
+///     PlaintextList::new(0u64, PlaintextCount(output_glwe.polynomial_size().0));
+///
+/// decrypt_glwe_ciphertext(
+///     &output_glwe_secret_key,
+///     &output_glwe,
+///     &mut decrypted_plaintext_list,
+/// );
+///
+/// // Round and remove encoding
+/// // First create a decomposer working on the high 4 bits corresponding to our encoding.
+/// let decomposer = SignedDecomposer::new(DecompositionBaseLog(4), DecompositionLevelCount(1));
+///
+/// decrypted_plaintext_list
+///     .iter_mut()
+///     .for_each(|x| *x.0 = decomposer.closest_representable(*x.0) >> 60);
+///
+/// // Check we recovered the original messages in the expected slots and zeros elsewhere
+/// for (idx, decrypted) in decrypted_plaintext_list.iter().enumerate() {
+///     let expected = if idx % 2 == 0 {
+///         *input_plaintext_list.get(idx / 2).0 >> 60
+///     } else {
+///         0
+///     };
+///     assert_eq!(*decrypted.0, expected);
+/// }
+/// ```
+pub fn par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees_with_thread_count<
+    Scalar,
+    KeyCont,
+    InputCont,
+    OutputCont,
+>(
+    lwe_pksk: &LwePackingKeyswitchKey<KeyCont>,
+    input_lwe_ciphertext_list: &LweCiphertextList<InputCont>,
+    output_glwe_ciphertext: &mut GlweCiphertext<OutputCont>,
+    monomial_degrees: &[MonomialDegree],
+    thread_count: ThreadCount,
+) where
+    Scalar: UnsignedInteger + Send + Sync,
+    KeyCont: Container<Element = Scalar> + Sync,
+    InputCont: Container<Element = Scalar>,
+    OutputCont: ContainerMut<Element = Scalar>,
+{
+    check_packing_keyswitch_inputs(lwe_pksk, input_lwe_ciphertext_list, output_glwe_ciphertext);
+    check_monomial_degrees(
+        input_lwe_ciphertext_list.lwe_ciphertext_count(),
+        output_glwe_ciphertext.polynomial_size(),
+        monomial_degrees,
     );
 
-    assert!(
-        input_lwe_ciphertext_list.lwe_ciphertext_count().0
-            <= output_glwe_ciphertext.polynomial_size().0
+    par_keyswitch_and_pack_at_monomial_degrees_unchecked(
+        lwe_pksk,
+        input_lwe_ciphertext_list,
+        output_glwe_ciphertext,
+        |idx| monomial_degrees[idx],
+        thread_count,
     );
+}
 
+fn par_keyswitch_and_pack_at_monomial_degrees_unchecked<Scalar, KeyCont, InputCont, OutputCont>(
+    lwe_pksk: &LwePackingKeyswitchKey<KeyCont>,
+    input_lwe_ciphertext_list: &LweCiphertextList<InputCont>,
+    output_glwe_ciphertext: &mut GlweCiphertext<OutputCont>,
+    monomial_degree: impl Fn(usize) -> MonomialDegree + Sync,
+    thread_count: ThreadCount,
+) where
+    Scalar: UnsignedInteger + Send + Sync,
+    KeyCont: Container<Element = Scalar> + Sync,
+    InputCont: Container<Element = Scalar>,
+    OutputCont: ContainerMut<Element = Scalar>,
+{
     assert!(
         thread_count.0 != 0,
         "Got thread_count == 0, this is not supported"
     );
 
+    if input_lwe_ciphertext_list.lwe_ciphertext_count().0 == 0 {
+        // Nothing to pack, the output is a trivial encryption of 0
+        output_glwe_ciphertext.as_mut().fill(Scalar::ZERO);
+        return;
+    }
+
     let output_glwe_size = output_glwe_ciphertext.glwe_size();
     let output_polynomial_size = output_glwe_ciphertext.polynomial_size();
     let output_ciphertext_modulus = output_glwe_ciphertext.ciphertext_modulus();
@@ -714,7 +1238,7 @@ pub fn par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_with_thread
         .0
         .div_ceil(thread_count);
 
-    // for each ciphertext, call mono_key_switch
+    // Each chunk is packed in its own buffer at the final degrees of its ciphertexts
     input_lwe_ciphertext_list
         .par_chunks(chunk_size)
         .enumerate()
@@ -726,23 +1250,14 @@ pub fn par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_with_thread
                 output_ciphertext_modulus,
             );
 
-            keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext(
+            let chunk_start = chunk_idx * chunk_size;
+            keyswitch_and_pack_at_monomial_degrees_unchecked(
                 lwe_pksk,
                 &input_ciphertext_list_chunk,
                 &mut buffer,
+                |idx| monomial_degree(chunk_start + idx),
             );
 
-            // Rotate to put the ciphertexts in the right slot
-            buffer
-                .as_mut_polynomial_list()
-                .iter_mut()
-                .for_each(|mut poly| {
-                    polynomial_wrapping_monic_monomial_mul_assign(
-                        &mut poly,
-                        MonomialDegree(chunk_idx * chunk_size),
-                    );
-                });
-
             buffer
         })
         .collect_into_vec(&mut intermediate_buffers);
@@ -202,6 +202,68 @@ where
     }
 }
 
+/// Accumulator computing the unbiased sample variance of a stream of values without storing them,
+/// using Welford's online algorithm.
+///
+/// ```rust
+/// use tfhe::core_crypto::algorithms::misc::StreamingVariance;
+///
+/// let mut acc = StreamingVariance::new();
+/// for sample in [1.0, 2.0, 3.0, 4.0] {
+///     acc.push(sample);
+/// }
+///
+/// assert_eq!(acc.count(), 4);
+/// assert_eq!(acc.mean(), 2.5);
+/// assert!((acc.finish().0 - 5.0 / 3.0).abs() < 1e-12);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StreamingVariance {
+    count: u64,
+    mean: f64,
+    sum_squared_deviations: f64,
+}
+
+impl StreamingVariance {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sample to the accumulator.
+    pub fn push(&mut self, sample: f64) {
+        self.count += 1;
+        let delta = sample - self.mean;
+        self.mean += delta / self.count as f64;
+        let delta_after_update = sample - self.mean;
+        self.sum_squared_deviations += delta * delta_after_update;
+    }
+
+    /// Number of samples pushed so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Mean of the samples pushed so far, 0 if no sample was pushed.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Return the unbiased sample variance of the pushed samples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if less than 2 samples were pushed.
+    pub fn finish(&self) -> Variance {
+        assert!(
+            self.count >= 2,
+            "StreamingVariance requires at least 2 samples, got {}",
+            self.count
+        );
+
+        Variance(self.sum_squared_deviations / (self.count - 1) as f64)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -299,4 +361,42 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_streaming_variance_matches_batch_variance() {
+        use crate::core_crypto::commons::test_tools::variance;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+
+        for num_samples in [2, 3, 100, 100_000] {
+            // Offset the samples to check the accumulator does not suffer from cancellation
+            let offset: f64 = rng.gen_range(-1e6..1e6);
+            let samples: Vec<f64> = (0..num_samples)
+                .map(|_| offset + rng.gen_range(-1.0..1.0))
+                .collect();
+
+            let mut acc = StreamingVariance::new();
+            for &sample in &samples {
+                acc.push(sample);
+            }
+
+            let expected = variance(&samples).0;
+            let streamed = acc.finish().0;
+
+            assert_eq!(acc.count(), num_samples as u64);
+            assert!(
+                (streamed - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+                "streamed={streamed}, expected={expected}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 2 samples")]
+    fn test_streaming_variance_single_sample_panics() {
+        let mut acc = StreamingVariance::new();
+        acc.push(1.0);
+        let _ = acc.finish();
+    }
 }
@@ -119,3 +119,89 @@ fn glwe_encrypt_sample_extract_decrypt_custom_mod<Scalar: UnsignedTorus + Send +
 }
 
 create_parametrized_test!(glwe_encrypt_sample_extract_decrypt_custom_mod);
+
+fn glwe_encrypt_extract_all_samples_decrypt_custom_mod<Scalar: UnsignedTorus + Send + Sync>(
+    params: ClassicTestParams<Scalar>,
+) {
+    let glwe_dimension = params.glwe_dimension;
+    let polynomial_size = params.polynomial_size;
+    let glwe_noise_distribution = params.glwe_noise_distribution;
+    let ciphertext_modulus = params.ciphertext_modulus;
+    let message_modulus_log = params.message_modulus_log;
+    let encoding_with_padding = get_encoding_with_padding(ciphertext_modulus);
+
+    let mut rsc = TestResources::new();
+
+    let msg_modulus = Scalar::ONE.shl(message_modulus_log.0);
+    let delta: Scalar = encoding_with_padding / msg_modulus;
+
+    for _ in 0..NB_TESTS {
+        let glwe_sk = allocate_and_generate_new_binary_glwe_secret_key(
+            glwe_dimension,
+            polynomial_size,
+            &mut rsc.secret_random_generator,
+        );
+
+        let equivalent_lwe_sk = glwe_sk.clone().into_lwe_secret_key();
+
+        let mut glwe = GlweCiphertext::new(
+            Scalar::ZERO,
+            glwe_dimension.to_glwe_size(),
+            polynomial_size,
+            ciphertext_modulus,
+        );
+
+        // Each slot gets a different message so that a mix up between slots is detected
+        let msgs: Vec<Scalar> = (0..polynomial_size.0)
+            .map(|idx| Scalar::cast_from(idx as u128) % msg_modulus)
+            .collect();
+
+        let plaintext_list =
+            PlaintextList::from_container(msgs.iter().map(|&msg| msg * delta).collect::<Vec<_>>());
+
+        encrypt_glwe_ciphertext(
+            &glwe_sk,
+            &mut glwe,
+            &plaintext_list,
+            glwe_noise_distribution,
+            &mut rsc.encryption_random_generator,
+        );
+
+        let mut output_lwe_ciphertext_list = LweCiphertextList::new(
+            Scalar::ZERO,
+            equivalent_lwe_sk.lwe_dimension().to_lwe_size(),
+            LweCiphertextCount(glwe.polynomial_size().0),
+            ciphertext_modulus,
+        );
+
+        extract_all_lwe_samples_from_glwe(&glwe, &mut output_lwe_ciphertext_list);
+
+        assert!(check_encrypted_content_respects_mod(
+            &output_lwe_ciphertext_list,
+            ciphertext_modulus
+        ));
+
+        for (idx, output_lwe_ciphertext) in output_lwe_ciphertext_list.iter().enumerate() {
+            let mut expected_lwe_ciphertext = LweCiphertext::new(
+                Scalar::ZERO,
+                equivalent_lwe_sk.lwe_dimension().to_lwe_size(),
+                ciphertext_modulus,
+            );
+
+            extract_lwe_sample_from_glwe_ciphertext(
+                &glwe,
+                &mut expected_lwe_ciphertext,
+                MonomialDegree(idx),
+            );
+
+            assert_eq!(output_lwe_ciphertext, expected_lwe_ciphertext.as_view());
+
+            let plaintext = decrypt_lwe_ciphertext(&equivalent_lwe_sk, &output_lwe_ciphertext);
+            let decoded = round_decode(plaintext.0, delta) % msg_modulus;
+
+            assert_eq!(decoded, msgs[idx]);
+        }
+    }
+}
+
+create_parametrized_test!(glwe_encrypt_extract_all_samples_decrypt_custom_mod);
@@ -122,3 +122,102 @@ fn test_seeded_lwe_ksk_gen_equivalence_u32_custom_mod() {
 fn test_seeded_lwe_ksk_gen_equivalence_u64_custom_mod() {
     test_seeded_lwe_ksk_gen_equivalence::<u64>(CiphertextModulus::try_new_power_of_2(63).unwrap());
 }
+
+fn test_parallel_lwe_ksk_gen_equivalence<Scalar: UnsignedTorus + Send + Sync>(
+    ciphertext_modulus: CiphertextModulus<Scalar>,
+) {
+    // DISCLAIMER: these toy example parameters are not guaranteed to be secure or yield correct
+    // computations
+    // Define parameters for LweKeyswitchKey creation
+    let input_lwe_dimension = LweDimension(742);
+    let lwe_noise_distribution =
+        DynamicDistribution::new_gaussian_from_std_dev(StandardDev(0.000007069849454709433));
+    let output_lwe_dimension = LweDimension(2048);
+    let decomp_base_log = DecompositionBaseLog(3);
+    let decomp_level_count = DecompositionLevelCount(5);
+
+    // Create the PRNG
+    let mut seeder = new_seeder();
+    let seeder = seeder.as_mut();
+    let mask_seed = seeder.seed();
+    let deterministic_seeder_seed = seeder.seed();
+    let mut secret_generator =
+        SecretRandomGenerator::<ActivatedRandomGenerator>::new(seeder.seed());
+
+    for _ in 0..NB_TESTS {
+        // Create the LweSecretKey
+        let input_lwe_secret_key = allocate_and_generate_new_binary_lwe_secret_key(
+            input_lwe_dimension,
+            &mut secret_generator,
+        );
+        let output_lwe_secret_key = allocate_and_generate_new_binary_lwe_secret_key(
+            output_lwe_dimension,
+            &mut secret_generator,
+        );
+
+        let mut deterministic_seeder =
+            DeterministicSeeder::<ActivatedRandomGenerator>::new(deterministic_seeder_seed);
+        let mut encryption_generator = EncryptionRandomGenerator::<ActivatedRandomGenerator>::new(
+            mask_seed,
+            &mut deterministic_seeder,
+        );
+
+        let ksk = allocate_and_generate_new_lwe_keyswitch_key(
+            &input_lwe_secret_key,
+            &output_lwe_secret_key,
+            decomp_base_log,
+            decomp_level_count,
+            lwe_noise_distribution,
+            ciphertext_modulus,
+            &mut encryption_generator,
+        );
+
+        let mut deterministic_seeder =
+            DeterministicSeeder::<ActivatedRandomGenerator>::new(deterministic_seeder_seed);
+        let mut encryption_generator = EncryptionRandomGenerator::<ActivatedRandomGenerator>::new(
+            mask_seed,
+            &mut deterministic_seeder,
+        );
+
+        let par_ksk = par_allocate_and_generate_new_lwe_keyswitch_key(
+            &input_lwe_secret_key,
+            &output_lwe_secret_key,
+            decomp_base_log,
+            decomp_level_count,
+            lwe_noise_distribution,
+            ciphertext_modulus,
+            &mut encryption_generator,
+        );
+
+        assert!(check_encrypted_content_respects_mod(
+            &par_ksk,
+            ciphertext_modulus
+        ));
+
+        assert_eq!(ksk, par_ksk);
+    }
+}
+
+#[test]
+fn test_parallel_lwe_ksk_gen_equivalence_u32_native_mod() {
+    test_parallel_lwe_ksk_gen_equivalence::<u32>(CiphertextModulus::new_native());
+}
+
+#[test]
+fn test_parallel_lwe_ksk_gen_equivalence_u64_native_mod() {
+    test_parallel_lwe_ksk_gen_equivalence::<u64>(CiphertextModulus::new_native());
+}
+
+#[test]
+fn test_parallel_lwe_ksk_gen_equivalence_u32_custom_mod() {
+    test_parallel_lwe_ksk_gen_equivalence::<u32>(
+        CiphertextModulus::try_new_power_of_2(31).unwrap(),
+    );
+}
+
+#[test]
+fn test_parallel_lwe_ksk_gen_equivalence_u64_custom_mod() {
+    test_parallel_lwe_ksk_gen_equivalence::<u64>(
+        CiphertextModulus::try_new_power_of_2(63).unwrap(),
+    );
+}
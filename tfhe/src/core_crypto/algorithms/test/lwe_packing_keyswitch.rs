@@ -222,3 +222,222 @@ where
 }
 
 create_parametrized_test!(lwe_list_encrypt_pks_to_glwe_decrypt_custom_mod);
+
+fn lwe_list_encrypt_pks_to_glwe_at_monomial_degrees_decrypt_custom_mod<Scalar, P>(params: P)
+where
+    Scalar: UnsignedTorus + Serialize + DeserializeOwned,
+    P: Into<PackingKeySwitchTestParams<Scalar>>,
+    PackingKeySwitchTestParams<Scalar>: KeyCacheAccess<Keys = PackingKeySwitchKeys<Scalar>>,
+{
+    let params = params.into();
+
+    let lwe_noise_distribution = params.lwe_noise_distribution;
+    let ciphertext_modulus = params.ciphertext_modulus;
+    let message_modulus_log = params.message_modulus_log;
+    let encoding_with_padding = get_encoding_with_padding(ciphertext_modulus);
+
+    let mut rsc = TestResources::new();
+
+    let msg_modulus = Scalar::ONE.shl(message_modulus_log.0);
+    let mut msg = msg_modulus;
+    let delta: Scalar = encoding_with_padding / msg_modulus;
+
+    while msg != Scalar::ZERO {
+        msg = msg.wrapping_sub(Scalar::ONE);
+        for _ in 0..NB_TESTS {
+            let mut keys_gen = |params| generate_keys(params, &mut rsc);
+            let keys = gen_keys_or_get_from_cache_if_enabled(params, &mut keys_gen);
+            let (pksk, lwe_sk, glwe_sk) = (keys.pksk, keys.lwe_sk, keys.glwe_sk);
+
+            let polynomial_size = glwe_sk.polynomial_size();
+
+            // Contiguous layout, must match the dedicated function
+            {
+                let input_count = polynomial_size.0 / 2;
+
+                let mut input_lwe_list = LweCiphertextList::new(
+                    Scalar::ZERO,
+                    lwe_sk.lwe_dimension().to_lwe_size(),
+                    LweCiphertextCount(input_count),
+                    ciphertext_modulus,
+                );
+
+                let input_plaintext_list =
+                    PlaintextList::new(msg * delta, PlaintextCount(input_count));
+
+                encrypt_lwe_ciphertext_list(
+                    &lwe_sk,
+                    &mut input_lwe_list,
+                    &input_plaintext_list,
+                    lwe_noise_distribution,
+                    &mut rsc.encryption_random_generator,
+                );
+
+                let mut output_glwe = GlweCiphertext::new(
+                    Scalar::ZERO,
+                    glwe_sk.glwe_dimension().to_glwe_size(),
+                    polynomial_size,
+                    ciphertext_modulus,
+                );
+
+                let mut output_glwe_contiguous = output_glwe.clone();
+
+                let monomial_degrees: Vec<_> = (0..input_count).map(MonomialDegree).collect();
+
+                keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees(
+                    &pksk,
+                    &input_lwe_list,
+                    &mut output_glwe,
+                    &monomial_degrees,
+                );
+
+                keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext(
+                    &pksk,
+                    &input_lwe_list,
+                    &mut output_glwe_contiguous,
+                );
+
+                assert_eq!(output_glwe.as_ref(), output_glwe_contiguous.as_ref());
+            }
+
+            // Sparse layout, inputs are put in reverse order every 3 coefficients
+            {
+                let monomial_degrees: Vec<_> = (0..polynomial_size.0)
+                    .rev()
+                    .step_by(3)
+                    .map(MonomialDegree)
+                    .collect();
+                let input_count = monomial_degrees.len();
+
+                let mut input_lwe_list = LweCiphertextList::new(
+                    Scalar::ZERO,
+                    lwe_sk.lwe_dimension().to_lwe_size(),
+                    LweCiphertextCount(input_count),
+                    ciphertext_modulus,
+                );
+
+                let input_plaintext_list =
+                    PlaintextList::new(msg * delta, PlaintextCount(input_count));
+
+                encrypt_lwe_ciphertext_list(
+                    &lwe_sk,
+                    &mut input_lwe_list,
+                    &input_plaintext_list,
+                    lwe_noise_distribution,
+                    &mut rsc.encryption_random_generator,
+                );
+
+                let mut output_glwe = GlweCiphertext::new(
+                    Scalar::ZERO,
+                    glwe_sk.glwe_dimension().to_glwe_size(),
+                    polynomial_size,
+                    ciphertext_modulus,
+                );
+
+                let mut output_glwe_parallel = output_glwe.clone();
+
+                keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees(
+                    &pksk,
+                    &input_lwe_list,
+                    &mut output_glwe,
+                    &monomial_degrees,
+                );
+
+                par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext_at_monomial_degrees(
+                    &pksk,
+                    &input_lwe_list,
+                    &mut output_glwe_parallel,
+                    &monomial_degrees,
+                );
+
+                assert_eq!(output_glwe.as_ref(), output_glwe_parallel.as_ref());
+
+                assert!(check_encrypted_content_respects_mod(
+                    &output_glwe,
+                    ciphertext_modulus
+                ));
+
+                let mut decrypted_plaintext_list =
+                    PlaintextList::new(Scalar::ZERO, PlaintextCount(polynomial_size.0));
+
+                decrypt_glwe_ciphertext(&glwe_sk, &output_glwe, &mut decrypted_plaintext_list);
+
+                for (idx, decrypted) in decrypted_plaintext_list.iter().enumerate() {
+                    let decoded = round_decode(*decrypted.0, delta) % msg_modulus;
+                    let expected = if monomial_degrees.contains(&MonomialDegree(idx)) {
+                        msg
+                    } else {
+                        Scalar::ZERO
+                    };
+                    assert_eq!(decoded, expected);
+                }
+            }
+        }
+
+        // In coverage, we break after one while loop iteration, changing message values does not
+        // yield higher coverage
+        #[cfg(tarpaulin)]
+        break;
+    }
+}
+
+create_parametrized_test!(lwe_list_encrypt_pks_to_glwe_at_monomial_degrees_decrypt_custom_mod);
+
+fn empty_lwe_list_pks_to_glwe_custom_mod<Scalar, P>(params: P)
+where
+    Scalar: UnsignedTorus + Serialize + DeserializeOwned,
+    P: Into<PackingKeySwitchTestParams<Scalar>>,
+    PackingKeySwitchTestParams<Scalar>: KeyCacheAccess<Keys = PackingKeySwitchKeys<Scalar>>,
+{
+    let params = params.into();
+
+    let ciphertext_modulus = params.ciphertext_modulus;
+
+    let mut rsc = TestResources::new();
+
+    let mut keys_gen = |params| generate_keys(params, &mut rsc);
+    let keys = gen_keys_or_get_from_cache_if_enabled(params, &mut keys_gen);
+    let (pksk, lwe_sk, glwe_sk) = (keys.pksk, keys.lwe_sk, keys.glwe_sk);
+
+    let input_lwe_list = LweCiphertextList::new(
+        Scalar::ZERO,
+        lwe_sk.lwe_dimension().to_lwe_size(),
+        LweCiphertextCount(0),
+        ciphertext_modulus,
+    );
+
+    // Stale data in the outputs must not leak in the result
+    let mut output_glwe = GlweCiphertext::new(
+        Scalar::ONE,
+        glwe_sk.glwe_dimension().to_glwe_size(),
+        glwe_sk.polynomial_size(),
+        ciphertext_modulus,
+    );
+
+    let mut output_glwe_parallel = GlweCiphertext::new(
+        Scalar::ONE,
+        glwe_sk.glwe_dimension().to_glwe_size(),
+        glwe_sk.polynomial_size(),
+        ciphertext_modulus,
+    );
+
+    keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext(
+        &pksk,
+        &input_lwe_list,
+        &mut output_glwe,
+    );
+
+    par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext(
+        &pksk,
+        &input_lwe_list,
+        &mut output_glwe_parallel,
+    );
+
+    assert!(output_glwe.as_ref().iter().all(|&x| x == Scalar::ZERO));
+    assert!(output_glwe_parallel
+        .as_ref()
+        .iter()
+        .all(|&x| x == Scalar::ZERO));
+}
+
+create_parametrized_test!(empty_lwe_list_pks_to_glwe_custom_mod);
@@ -5,6 +5,7 @@ use crate::conformance::ParameterSetConformant;
 use crate::core_crypto::backward_compatibility::entities::compressed_modulus_switched_glwe_ciphertext::CompressedModulusSwitchedGlweCiphertextVersions;
 use crate::core_crypto::fft_impl::common::modulus_switch;
 use crate::core_crypto::prelude::*;
+use crate::named::Named;
 
 /// An object to store a ciphertext using less memory
 /// The modulus of the ciphertext is decreased by rounding and the result is stored in a compact way
@@ -87,6 +88,10 @@ pub struct CompressedModulusSwitchedGlweCiphertext<Scalar: UnsignedInteger> {
     pub(crate) uncompressed_ciphertext_modulus: CiphertextModulus<Scalar>,
 }
 
+impl<Scalar: UnsignedInteger> Named for CompressedModulusSwitchedGlweCiphertext<Scalar> {
+    const NAME: &'static str = "core_crypto::CompressedModulusSwitchedGlweCiphertext";
+}
+
 impl<Scalar: UnsignedTorus> CompressedModulusSwitchedGlweCiphertext<Scalar> {
     pub fn glwe_dimension(&self) -> GlweDimension {
         self.glwe_dimension
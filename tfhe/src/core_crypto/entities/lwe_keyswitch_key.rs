@@ -4,6 +4,8 @@ use tfhe_versionable::Versionize;
 
 use crate::conformance::ParameterSetConformant;
 use crate::core_crypto::backward_compatibility::entities::lwe_keyswitch_key::LweKeyswitchKeyVersions;
+use crate::core_crypto::commons::generators::EncryptionRandomGeneratorForkConfig;
+use crate::core_crypto::commons::math::random::{Distribution, RandomGenerable};
 use crate::core_crypto::commons::parameters::*;
 use crate::core_crypto::commons::traits::*;
 use crate::core_crypto::entities::*;
@@ -111,6 +113,41 @@ pub fn lwe_keyswitch_key_input_key_element_encrypted_size(
     decomp_level_count.0 * output_lwe_size.0
 }
 
+/// Return the [`EncryptionRandomGeneratorForkConfig`] yielding one generator per input
+/// [`LweSecretKey`] element encrypted in an [`LweKeyswitchKey`].
+pub fn lwe_keyswitch_key_encryption_fork_config<Scalar, MaskDistribution, NoiseDistribution>(
+    input_lwe_dimension: LweDimension,
+    decomp_level_count: DecompositionLevelCount,
+    output_lwe_dimension: LweDimension,
+    mask_distribution: MaskDistribution,
+    noise_distribution: NoiseDistribution,
+    ciphertext_modulus: CiphertextModulus<Scalar>,
+) -> EncryptionRandomGeneratorForkConfig
+where
+    Scalar: UnsignedInteger
+        + RandomGenerable<MaskDistribution, CustomModulus = Scalar>
+        + RandomGenerable<NoiseDistribution, CustomModulus = Scalar>,
+    MaskDistribution: Distribution,
+    NoiseDistribution: Distribution,
+{
+    // One ciphertext per level encrypted under the output key
+    let lev_mask_sample_count =
+        decomp_level_count.0 * lwe_ciphertext_encryption_mask_sample_count(output_lwe_dimension);
+    let lev_noise_sample_count =
+        decomp_level_count.0 * lwe_ciphertext_encryption_noise_sample_count();
+
+    let modulus = ciphertext_modulus.get_custom_modulus_as_optional_scalar();
+
+    EncryptionRandomGeneratorForkConfig::new(
+        input_lwe_dimension.0,
+        lev_mask_sample_count,
+        mask_distribution,
+        lev_noise_sample_count,
+        noise_distribution,
+        modulus,
+    )
+}
+
 impl<Scalar: UnsignedInteger, C: Container<Element = Scalar>> LweKeyswitchKey<C> {
     /// Create an [`LweKeyswitchKey`] from an existing container.
     ///
@@ -285,6 +322,27 @@ impl<Scalar: UnsignedInteger, C: Container<Element = Scalar>> LweKeyswitchKey<C>
     pub fn ciphertext_modulus(&self) -> CiphertextModulus<C::Element> {
         self.ciphertext_modulus
     }
+
+    pub fn encryption_fork_config<MaskDistribution, NoiseDistribution>(
+        &self,
+        mask_distribution: MaskDistribution,
+        noise_distribution: NoiseDistribution,
+    ) -> EncryptionRandomGeneratorForkConfig
+    where
+        MaskDistribution: Distribution,
+        NoiseDistribution: Distribution,
+        Scalar: RandomGenerable<MaskDistribution, CustomModulus = Scalar>
+            + RandomGenerable<NoiseDistribution, CustomModulus = Scalar>,
+    {
+        lwe_keyswitch_key_encryption_fork_config(
+            self.input_key_lwe_dimension(),
+            self.decomposition_level_count(),
+            self.output_key_lwe_dimension(),
+            mask_distribution,
+            noise_distribution,
+            self.ciphertext_modulus(),
+        )
+    }
 }
 
 impl<Scalar: UnsignedInteger, C: ContainerMut<Element = Scalar>> LweKeyswitchKey<C> {
@@ -1,6 +1,7 @@
 use super::{DataKind, Expandable, RadixCiphertext, SignedRadixCiphertext};
 use crate::integer::backward_compatibility::ciphertext::CompressedCiphertextListVersions;
 use crate::integer::compression_keys::{CompressionKey, DecompressionKey};
+use crate::integer::key_switching_key::KeySwitchingKey;
 use crate::integer::BooleanBlock;
 use crate::shortint::ciphertext::CompressedCiphertextList as ShortintCompressedCiphertextList;
 use crate::shortint::Ciphertext;
@@ -111,11 +112,7 @@ impl CompressedCiphertextList {
         self.len() == 0
     }
 
-    fn blocks_of(
-        &self,
-        index: usize,
-        decomp_key: &DecompressionKey,
-    ) -> Option<(Vec<Ciphertext>, DataKind)> {
+    fn block_range_of(&self, index: usize) -> Option<(usize, DataKind)> {
         let preceding_infos = self.info.get(..index)?;
         let current_info = self.info.get(index).copied()?;
 
@@ -125,6 +122,16 @@ impl CompressedCiphertextList {
             .map(DataKind::num_blocks)
             .sum();
 
+        Some((start_block_index, current_info))
+    }
+
+    fn blocks_of(
+        &self,
+        index: usize,
+        decomp_key: &DecompressionKey,
+    ) -> Option<(Vec<Ciphertext>, DataKind)> {
+        let (start_block_index, current_info) = self.block_range_of(index)?;
+
         let end_block_index = start_block_index + current_info.num_blocks();
 
         Some((
@@ -136,6 +143,41 @@ impl CompressedCiphertextList {
         ))
     }
 
+    /// Extract the blocks of the value at `index` without applying the final PBS of the
+    /// decompression, see
+    /// [`unpack_mod_switched`](crate::shortint::list_compression::DecompressionKey::unpack_mod_switched)
+    /// for what the returned blocks are.
+    ///
+    /// This is meant for noise analysis of the compression. Applying
+    /// [`bootstrap_mod_switched`](crate::shortint::list_compression::DecompressionKey::bootstrap_mod_switched)
+    /// to each block gives the blocks of the value returned by [`Self::get`].
+    pub fn extract_mod_switched(
+        &self,
+        index: usize,
+        decomp_key: &DecompressionKey,
+    ) -> crate::Result<(Vec<Ciphertext>, DataKind)> {
+        let (start_block_index, current_info) = self.block_range_of(index).ok_or_else(|| {
+            crate::Error::new(format!(
+                "Index {index} is out of bounds for a CompressedCiphertextList of length {}",
+                self.len()
+            ))
+        })?;
+
+        let end_block_index = start_block_index + current_info.num_blocks();
+
+        let blocks = (start_block_index..end_block_index)
+            .into_par_iter()
+            .map(|i| {
+                decomp_key
+                    .key
+                    .unpack_mod_switched(&self.packed_list, i)
+                    .unwrap()
+            })
+            .collect();
+
+        Ok((blocks, current_info))
+    }
+
     pub fn get_kind_of(&self, index: usize) -> Option<DataKind> {
         self.info.get(index).copied()
     }
@@ -148,14 +190,63 @@ impl CompressedCiphertextList {
             .map(|(blocks, kind)| T::from_expanded_blocks(blocks, kind))
             .transpose()
     }
+
+    /// Decompress the value at `index` and cast it with `key_switching_key`.
+    ///
+    /// The result decrypts to the same value as [`Self::get`] followed by
+    /// [`KeySwitchingKey::cast`]. The cast lookup tables are generated once for all the blocks
+    /// and, when casting to a smaller modulus, the first PBS of the cast is merged in the
+    /// decompression PBS, see
+    /// [`unpack_and_cast_with_lookup_tables`](crate::shortint::KeySwitchingKey::unpack_and_cast_with_lookup_tables).
+    ///
+    /// The `decomp_key` must have been generated from the source client key of
+    /// `key_switching_key`.
+    pub fn get_cast<T>(
+        &self,
+        index: usize,
+        decomp_key: &DecompressionKey,
+        key_switching_key: &KeySwitchingKey,
+    ) -> crate::Result<Option<T>>
+    where
+        T: Expandable,
+    {
+        let Some((start_block_index, current_info)) = self.block_range_of(index) else {
+            return Ok(None);
+        };
+
+        let end_block_index = start_block_index + current_info.num_blocks();
+
+        let lookup_tables = key_switching_key.key.cast_lookup_tables();
+
+        let blocks = (start_block_index..end_block_index)
+            .into_par_iter()
+            .map(|i| {
+                key_switching_key
+                    .key
+                    .unpack_and_cast_with_lookup_tables(
+                        &self.packed_list,
+                        i,
+                        &decomp_key.key,
+                        lookup_tables,
+                    )
+                    .unwrap()
+            })
+            .collect();
+
+        T::from_expanded_blocks(blocks, current_info).map(Some)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::integer::keycache::KEY_CACHE;
     use crate::integer::{gen_keys, IntegerKeyKind};
     use crate::shortint::parameters::list_compression::COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
-    use crate::shortint::parameters::PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
+    use crate::shortint::parameters::{
+        ShortintKeySwitchingParameters, PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
+    };
     use itertools::Itertools;
     use rand::Rng;
 
@@ -342,4 +433,128 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_ciphertext_compression_extract_mod_switched() {
+        const NUM_BLOCKS: usize = 8;
+
+        let (cks, sks) = gen_keys(
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
+            IntegerKeyKind::Radix,
+        );
+
+        let private_compression_key =
+            cks.new_compression_private_key(COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+
+        let (compression_key, decompression_key) =
+            cks.new_compression_decompression_keys(&private_compression_key);
+
+        let mut rng = rand::thread_rng();
+
+        let message_modulus: u128 = cks.parameters().message_modulus().0 as u128;
+        let modulus = message_modulus.pow(NUM_BLOCKS as u32);
+
+        let messages = (0..3)
+            .map(|_| rng.gen::<u128>() % modulus)
+            .collect::<Vec<_>>();
+
+        let mut builder = CompressedCiphertextListBuilder::new();
+
+        for message in messages.iter() {
+            let ct = cks.encrypt_radix(*message, NUM_BLOCKS);
+            let and_ct = sks.bitand_parallelized(&ct, &ct);
+            builder.push(and_ct);
+        }
+
+        let compressed = builder.build(&compression_key);
+
+        for (i, message) in messages.iter().enumerate() {
+            let (mod_switched, kind) = compressed
+                .extract_mod_switched(i, &decompression_key)
+                .unwrap();
+            assert_eq!(kind, DataKind::Unsigned(NUM_BLOCKS));
+            assert_eq!(mod_switched.len(), NUM_BLOCKS);
+
+            let blocks = mod_switched
+                .iter()
+                .map(|block| decompression_key.key.bootstrap_mod_switched(block))
+                .collect::<Vec<_>>();
+
+            let expected: RadixCiphertext = compressed.get(i, &decompression_key).unwrap().unwrap();
+            assert_eq!(blocks, expected.blocks);
+
+            let decrypted: u128 = cks.decrypt_radix(&RadixCiphertext::from(blocks));
+            assert_eq!(decrypted, *message);
+        }
+
+        assert!(compressed
+            .extract_mod_switched(messages.len(), &decompression_key)
+            .is_err());
+    }
+
+    #[test]
+    fn test_ciphertext_compression_get_cast() {
+        const NUM_BLOCKS: usize = 8;
+
+        let (cks, sks) = gen_keys(
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
+            IntegerKeyKind::Radix,
+        );
+        let (cks_2, sks_2) = KEY_CACHE.get_from_params(
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            IntegerKeyKind::Radix,
+        );
+
+        let private_compression_key =
+            cks.new_compression_private_key(COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+
+        let (compression_key, decompression_key) =
+            cks.new_compression_decompression_keys(&private_compression_key);
+
+        let ksk_params = ShortintKeySwitchingParameters::new(
+            cks_2.parameters().ks_base_log(),
+            cks_2.parameters().ks_level(),
+            cks_2.parameters().encryption_key_choice(),
+        );
+        let ksk = KeySwitchingKey::new((&cks, Some(&sks)), (&cks_2, &sks_2), ksk_params);
+
+        let mut rng = rand::thread_rng();
+
+        let message_modulus: u128 = cks.parameters().message_modulus().0 as u128;
+        let modulus = message_modulus.pow(NUM_BLOCKS as u32);
+
+        let messages = (0..3)
+            .map(|_| rng.gen::<u128>() % modulus)
+            .collect::<Vec<_>>();
+
+        let mut builder = CompressedCiphertextListBuilder::new();
+
+        for message in messages.iter() {
+            let ct = cks.encrypt_radix(*message, NUM_BLOCKS);
+            let and_ct = sks.bitand_parallelized(&ct, &ct);
+            builder.push(and_ct);
+        }
+
+        let compressed = builder.build(&compression_key);
+
+        for (i, message) in messages.iter().enumerate() {
+            let cast: RadixCiphertext = compressed
+                .get_cast(i, &decompression_key, &ksk)
+                .unwrap()
+                .unwrap();
+
+            let decompressed: RadixCiphertext =
+                compressed.get(i, &decompression_key).unwrap().unwrap();
+            let expected = ksk.cast(&decompressed);
+            assert_eq!(cast.blocks, expected.blocks);
+
+            let decrypted: u128 = cks_2.decrypt_radix(&cast);
+            assert_eq!(decrypted, *message);
+        }
+
+        assert!(compressed
+            .get_cast::<RadixCiphertext>(messages.len(), &decompression_key, &ksk)
+            .unwrap()
+            .is_none());
+    }
 }
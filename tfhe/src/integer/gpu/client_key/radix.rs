@@ -11,7 +11,7 @@ use crate::integer::gpu::list_compression::server_keys::{
 use crate::integer::gpu::server_key::CudaBootstrappingKey;
 use crate::integer::RadixClientKey;
 use crate::shortint::engine::ShortintEngine;
-use crate::shortint::{ClassicPBSParameters, EncryptionKeyChoice, PBSParameters};
+use crate::shortint::{ClassicPBSParameters, PBSParameters};
 
 impl RadixClientKey {
     pub fn new_cuda_compression_decompression_keys(
@@ -29,11 +29,9 @@ impl RadixClientKey {
         };
         let params = &private_compression_key.params;
 
-        assert_eq!(
-            cks_params.encryption_key_choice,
-            EncryptionKeyChoice::Big,
-            "Compression is only compatible with ciphertext in post PBS dimension"
-        );
+        params
+            .validate_against(cks_params)
+            .unwrap_or_else(|err| panic!("Invalid compression parameters: {err}"));
 
         // Compression key
         let packing_key_switching_key = ShortintEngine::with_thread_local_mut(|engine| {
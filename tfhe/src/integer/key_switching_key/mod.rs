@@ -5,7 +5,7 @@ use super::backward_compatibility::key_switching_key::{
 use super::{ClientKey, CompressedServerKey, ServerKey};
 use crate::conformance::ParameterSetConformant;
 use crate::integer::client_key::secret_encryption_key::SecretEncryptionKeyView;
-use crate::integer::IntegerCiphertext;
+use crate::integer::{IntegerCiphertext, RadixCiphertext};
 use crate::shortint::key_switching_key::KeySwitchingKeyConformanceParams;
 use crate::shortint::parameters::ShortintKeySwitchingParameters;
 use rayon::prelude::*;
@@ -142,11 +142,14 @@ impl KeySwitchingKey {
     }
 
     pub fn cast<Int: IntegerCiphertext>(&self, ct: &Int) -> Int {
+        // The lookup tables only depend on the keys, they are generated once and cached in the key
+        let lookup_tables = self.key.cast_lookup_tables();
+
         Int::from_blocks(
             ct.blocks()
                 .par_iter()
                 .map(|b| {
-                    let mut ret = self.key.cast(b);
+                    let mut ret = self.key.cast_with_lookup_tables(b, lookup_tables);
 
                     // These next 2 lines are to handle Crt ciphertexts
                     ret.message_modulus = b.message_modulus;
@@ -158,6 +161,28 @@ impl KeySwitchingKey {
         )
     }
 
+    /// Cast a [`RadixCiphertext`] block by block, keeping the order and the number of blocks.
+    ///
+    /// Returns an error if the destination message modulus is not the one of the input blocks, as
+    /// the output would need to be re-blocked to keep the same value.
+    pub fn cast_radix(&self, input: &RadixCiphertext) -> crate::Result<RadixCiphertext> {
+        let dest_message_modulus = self.key.dest_server_key.message_modulus;
+
+        if let Some(block) = input
+            .blocks
+            .iter()
+            .find(|block| block.message_modulus != dest_message_modulus)
+        {
+            return Err(crate::Error::new(format!(
+                "Cannot cast a RadixCiphertext with blocks of message modulus {:?} \
+                to a destination message modulus {:?}, re-blocking is not supported",
+                block.message_modulus, dest_message_modulus
+            )));
+        }
+
+        Ok(self.cast(input))
+    }
+
     pub fn as_view(&self) -> KeySwitchingKeyView<'_> {
         KeySwitchingKeyView {
             key: self.key.as_view(),
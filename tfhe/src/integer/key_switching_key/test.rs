@@ -5,6 +5,7 @@ use crate::integer::{
     ClientKey, CompactPrivateKey, CompactPublicKey, CrtClientKey, IntegerCiphertext,
     IntegerKeyKind, RadixCiphertext, RadixClientKey, ServerKey,
 };
+use crate::shortint::engine::ShortintEngine;
 use crate::shortint::parameters::compact_public_key_only::p_fail_2_minus_64::ks_pbs::PARAM_PKE_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
 use crate::shortint::parameters::key_switching::p_fail_2_minus_64::ks_pbs::{
     PARAM_KEYSWITCH_PKE_TO_BIG_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
@@ -13,8 +14,9 @@ use crate::shortint::parameters::key_switching::p_fail_2_minus_64::ks_pbs::{
 use crate::shortint::parameters::{
     ClassicPBSParameters, CompactPublicKeyEncryptionParameters, ShortintKeySwitchingParameters,
     PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
-    PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
+    PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64, PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
 };
+use std::sync::Arc;
 
 #[test]
 fn gen_multi_keys_test_rdxinteger_to_rdxinteger_ci_run_filter() {
@@ -257,3 +259,99 @@ fn test_cpk_encrypt_cast_to_big_compute_big_ci_run_filter() {
         PARAM_KEYSWITCH_PKE_TO_BIG_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
     )
 }
+
+#[test]
+fn gen_multi_keys_test_cast_radix_ci_run_filter() {
+    let num_block = 4;
+
+    let client_key_1 = RadixClientKey::new(PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64, num_block);
+
+    let (client_key_2, server_key_2) = KEY_CACHE.get_from_params(
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        IntegerKeyKind::Radix,
+    );
+    let client_key_2 = RadixClientKey::from((client_key_2, num_block));
+
+    let ksk_params = ShortintKeySwitchingParameters::new(
+        client_key_2.parameters().ks_base_log(),
+        client_key_2.parameters().ks_level(),
+        client_key_2.parameters().encryption_key_choice(),
+    );
+    let ksk = KeySwitchingKey::new(
+        (&client_key_1, None),
+        (&client_key_2, &server_key_2),
+        ksk_params,
+    );
+
+    let ct1 = client_key_1.encrypt(228u8);
+    let ct2 = ksk.cast_radix(&ct1).unwrap();
+
+    assert_eq!(ct2.blocks.len(), num_block);
+    let clear: u64 = client_key_2.decrypt(&ct2);
+    assert_eq!(clear, 228);
+
+    // 2_2 blocks cannot be cast to 3_3 blocks without re-blocking the radix
+    let (client_key_3, server_key_3) = KEY_CACHE.get_from_params(
+        PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
+        IntegerKeyKind::Radix,
+    );
+    let ksk_params = ShortintKeySwitchingParameters::new(
+        client_key_3.parameters().ks_base_log(),
+        client_key_3.parameters().ks_level(),
+        client_key_3.parameters().encryption_key_choice(),
+    );
+    let ksk = KeySwitchingKey::new(
+        (&client_key_1, None),
+        (&client_key_3, &server_key_3),
+        ksk_params,
+    );
+
+    assert!(ksk.cast_radix(&ct1).is_err());
+}
+
+#[test]
+fn gen_multi_keys_test_cast_identity_lookup_table_built_once_ci_run_filter() {
+    let num_block = 4;
+
+    let client_key_1 = RadixClientKey::new(PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64, num_block);
+
+    let (client_key_2, server_key_2) = KEY_CACHE.get_from_params(
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        IntegerKeyKind::Radix,
+    );
+    let client_key_2 = RadixClientKey::from((client_key_2, num_block));
+
+    let ksk_params = ShortintKeySwitchingParameters::new(
+        client_key_2.parameters().ks_base_log(),
+        client_key_2.parameters().ks_level(),
+        client_key_2.parameters().encryption_key_choice(),
+    );
+    // Two casting keys to the same destination server key
+    let ksks = [(); 2].map(|()| {
+        KeySwitchingKey::new(
+            (&client_key_1, None),
+            (&client_key_2, &server_key_2),
+            ksk_params,
+        )
+    });
+
+    let identity_lut = ShortintEngine::with_thread_local_mut(|engine| {
+        engine.identity_lookup_table(&server_key_2.key)
+    });
+
+    for ksk in &ksks {
+        for msg in [228u64, 17] {
+            let ct1 = client_key_1.encrypt(msg);
+            let ct2: RadixCiphertext = ksk.cast(&ct1);
+            let clear: u64 = client_key_2.decrypt(&ct2);
+            assert_eq!(clear, msg);
+        }
+
+        // All the blocks of all the casts to the destination server key used the identity lookup
+        // table built once for it
+        assert!(Arc::ptr_eq(
+            &ksk.key.cast_lookup_tables().post_processing,
+            &identity_lut
+        ));
+    }
+}
@@ -2,9 +2,9 @@ use tfhe_versionable::deprecation::{Deprecable, Deprecated};
 use tfhe_versionable::VersionsDispatch;
 
 use crate::shortint::key_switching_key::{
-    CompressedKeySwitchingKeyMaterial, KeySwitchingKeyMaterial,
+    CompressedKeySwitchingKeyMaterial, KeySwitchingKeyMaterial, SerializableKeySwitchingKey,
 };
-use crate::shortint::{CompressedKeySwitchingKey, KeySwitchingKey};
+use crate::shortint::CompressedKeySwitchingKey;
 
 #[derive(VersionsDispatch)]
 pub enum KeySwitchingKeyMaterialVersions {
@@ -13,7 +13,7 @@ pub enum KeySwitchingKeyMaterialVersions {
 
 #[derive(VersionsDispatch)]
 pub enum KeySwitchingKeyVersions {
-    V0(KeySwitchingKey),
+    V0(SerializableKeySwitchingKey),
 }
 
 impl Deprecable for CompressedKeySwitchingKeyMaterial {
@@ -17,9 +17,11 @@ use crate::core_crypto::prelude::{ContainerMut, GlweSize};
 use crate::core_crypto::seeders::new_seeder;
 use crate::shortint::ciphertext::{Degree, MaxDegree};
 use crate::shortint::prelude::PolynomialSize;
+use crate::shortint::server_key::LookupTableOwned;
 use crate::shortint::{CarryModulus, MessageModulus, ServerKey};
 use std::cell::RefCell;
 use std::fmt::Debug;
+use std::sync::Arc;
 
 mod client_side;
 mod public_side;
@@ -287,6 +289,31 @@ pub struct ShortintEngine {
     pub(crate) random_generator: RandomGenerator<ActivatedRandomGenerator>,
     pub(crate) computation_buffers: ComputationBuffers,
     ciphertext_buffers: Memory,
+    /// Identity lookup tables already generated by this engine, see
+    /// [`Self::identity_lookup_table`].
+    identity_lookup_tables: Vec<(LookupTableParameters, Arc<LookupTableOwned>)>,
+}
+
+/// The parameters of a [`ServerKey`] which fully determine the lookup tables it generates.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct LookupTableParameters {
+    glwe_size: GlweSize,
+    polynomial_size: PolynomialSize,
+    ciphertext_modulus: CiphertextModulus,
+    message_modulus: MessageModulus,
+    carry_modulus: CarryModulus,
+}
+
+impl From<&ServerKey> for LookupTableParameters {
+    fn from(server_key: &ServerKey) -> Self {
+        Self {
+            glwe_size: server_key.bootstrapping_key.glwe_size(),
+            polynomial_size: server_key.bootstrapping_key.polynomial_size(),
+            ciphertext_modulus: server_key.ciphertext_modulus,
+            message_modulus: server_key.message_modulus,
+            carry_modulus: server_key.carry_modulus,
+        }
+    }
 }
 
 impl ShortintEngine {
@@ -333,6 +360,7 @@ impl ShortintEngine {
             seeder: deterministic_seeder,
             computation_buffers: ComputationBuffers::default(),
             ciphertext_buffers: Memory::default(),
+            identity_lookup_tables: Vec::new(),
         }
     }
 
@@ -354,6 +382,31 @@ impl ShortintEngine {
         )
     }
 
+    /// Return the identity lookup table of the given [`ServerKey`].
+    ///
+    /// It is generated the first time it is requested for the parameters of `server_key` and then
+    /// shared by all the server keys with the same parameters, e.g. by all the casts to the same
+    /// destination key done on this thread.
+    pub(crate) fn identity_lookup_table(
+        &mut self,
+        server_key: &ServerKey,
+    ) -> Arc<LookupTableOwned> {
+        let parameters = LookupTableParameters::from(server_key);
+
+        if let Some((_, lookup_table)) = self
+            .identity_lookup_tables
+            .iter()
+            .find(|(cached_parameters, _)| *cached_parameters == parameters)
+        {
+            return Arc::clone(lookup_table);
+        }
+
+        let lookup_table = Arc::new(server_key.generate_lookup_table(|x| x));
+        self.identity_lookup_tables
+            .push((parameters, Arc::clone(&lookup_table)));
+        lookup_table
+    }
+
     pub fn get_buffers_no_sk(
         &mut self,
         in_dim: LweDimension,
@@ -253,6 +253,35 @@ impl ShortintEngine {
         )
     }
 
+    pub(crate) fn par_new_key_switching_key(
+        &mut self,
+        input_secret_key: &SecretEncryptionKeyView<'_>,
+        output_client_key: &ClientKey,
+        params: ShortintKeySwitchingParameters,
+    ) -> LweKeyswitchKeyOwned<u64> {
+        let (output_secret_key, encryption_noise) = match params.destination_key {
+            EncryptionKeyChoice::Big => (
+                output_client_key.large_lwe_secret_key(),
+                output_client_key.parameters.glwe_noise_distribution(),
+            ),
+            EncryptionKeyChoice::Small => (
+                output_client_key.small_lwe_secret_key(),
+                output_client_key.parameters.lwe_noise_distribution(),
+            ),
+        };
+
+        // Creation of the key switching key
+        par_allocate_and_generate_new_lwe_keyswitch_key(
+            &input_secret_key.lwe_secret_key,
+            &output_secret_key,
+            params.ks_base_log,
+            params.ks_level,
+            encryption_noise,
+            output_client_key.parameters.ciphertext_modulus(),
+            &mut self.encryption_generator,
+        )
+    }
+
     pub(crate) fn new_seeded_key_switching_key(
         &mut self,
         input_secret_key: &SecretEncryptionKeyView<'_>,
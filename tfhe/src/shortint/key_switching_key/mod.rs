@@ -3,21 +3,30 @@
 //! - [KeySwitchingKey] allows switching the keys of a ciphertext, from a cleitn key to another.
 
 use crate::conformance::ParameterSetConformant;
+use crate::core_crypto::commons::dispersion::Variance;
+use crate::core_crypto::commons::noise_formulas::lwe_keyswitch::keyswitch_additive_variance_132_bits_security_gaussian;
+use crate::core_crypto::commons::noise_formulas::lwe_programmable_bootstrap::pbs_variance_132_bits_security_gaussian;
 use crate::core_crypto::prelude::{
     decompress_seeded_lwe_keyswitch_key, keyswitch_lwe_ciphertext, ActivatedRandomGenerator,
-    KeyswitchKeyConformanceParams, LweKeyswitchKeyOwned, SeededLweKeyswitchKeyOwned,
+    DecompositionBaseLog, DecompositionLevelCount, KeyswitchKeyConformanceParams, LweDimension,
+    LweKeyswitchKeyOwned, SeededLweKeyswitchKeyOwned,
 };
-use crate::shortint::ciphertext::Degree;
+use crate::shortint::ciphertext::{CompressedCiphertextList, Degree};
 use crate::shortint::client_key::secret_encryption_key::SecretEncryptionKeyView;
 use crate::shortint::engine::ShortintEngine;
+use crate::shortint::list_compression::DecompressionKey;
 use crate::shortint::parameters::{
-    EncryptionKeyChoice, NoiseLevel, PBSOrder, ShortintKeySwitchingParameters,
+    CarryModulus, EncryptionKeyChoice, MessageModulus, NoiseLevel, PBSOrder,
+    ShortintKeySwitchingParameters,
 };
-use crate::shortint::server_key::apply_programmable_bootstrap;
+use crate::shortint::server_key::{apply_programmable_bootstrap, LookupTableOwned};
 use crate::shortint::{Ciphertext, ClientKey, CompressedServerKey, ServerKey};
 use core::cmp::Ordering;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::{Arc, OnceLock};
 use tfhe_versionable::Versionize;
 
 use super::backward_compatibility::key_switching_key::{
@@ -28,6 +37,129 @@ use super::backward_compatibility::key_switching_key::{
 #[cfg(test)]
 mod test;
 
+/// Error returned when a cast between two sets of moduli is not supported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CastError {
+    NonPowerOfTwoInputModulus { full_message_modulus: usize },
+    NonPowerOfTwoOutputModulus { full_message_modulus: usize },
+}
+
+impl Display for CastError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NonPowerOfTwoInputModulus {
+                full_message_modulus,
+            } => {
+                write!(
+                    f,
+                    "The input full message modulus (={full_message_modulus}) is not a power of 2"
+                )
+            }
+            Self::NonPowerOfTwoOutputModulus {
+                full_message_modulus,
+            } => {
+                write!(
+                    f,
+                    "The output full message modulus (={full_message_modulus}) is not a power of 2"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CastError {}
+
+/// Compute the shift applied to the message when casting from the input moduli to the output
+/// moduli, i.e. the difference between the number of bits of the output full message modulus and
+/// the input one.
+///
+/// A positive value means the message is cast to a bigger modulus, a negative value to a smaller
+/// one.
+///
+/// # Example
+///
+/// ```rust
+/// use tfhe::shortint::key_switching_key::compute_cast_rshift;
+/// use tfhe::shortint::parameters::{CarryModulus, MessageModulus};
+///
+/// let cast_rshift = compute_cast_rshift(
+///     MessageModulus(4),
+///     CarryModulus(4),
+///     MessageModulus(2),
+///     CarryModulus(2),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(cast_rshift, -2);
+/// ```
+pub fn compute_cast_rshift(
+    input_message_modulus: MessageModulus,
+    input_carry_modulus: CarryModulus,
+    output_message_modulus: MessageModulus,
+    output_carry_modulus: CarryModulus,
+) -> Result<i8, CastError> {
+    let full_message_modulus_input = input_carry_modulus.0 * input_message_modulus.0;
+    let full_message_modulus_output = output_carry_modulus.0 * output_message_modulus.0;
+
+    if !full_message_modulus_input.is_power_of_two() {
+        return Err(CastError::NonPowerOfTwoInputModulus {
+            full_message_modulus: full_message_modulus_input,
+        });
+    }
+    if !full_message_modulus_output.is_power_of_two() {
+        return Err(CastError::NonPowerOfTwoOutputModulus {
+            full_message_modulus: full_message_modulus_output,
+        });
+    }
+
+    let nb_bits_input: i8 = full_message_modulus_input.ilog2().try_into().unwrap();
+    let nb_bits_output: i8 = full_message_modulus_output.ilog2().try_into().unwrap();
+
+    Ok(nb_bits_output - nb_bits_input)
+}
+
+/// Check the `cast_rshift` of a casting key against the moduli of the server keys it casts
+/// between.
+///
+/// Without source moduli only the sign of `cast_rshift` can be checked: casting to a smaller
+/// modulus requires the source server key.
+fn check_cast_rshift(
+    src_moduli: Option<(MessageModulus, CarryModulus)>,
+    dst_moduli: (MessageModulus, CarryModulus),
+    cast_rshift: i8,
+) -> Result<(), String> {
+    match src_moduli {
+        Some((src_message_modulus, src_carry_modulus)) => {
+            let (dst_message_modulus, dst_carry_modulus) = dst_moduli;
+
+            let expected_cast_rshift = compute_cast_rshift(
+                src_message_modulus,
+                src_carry_modulus,
+                dst_message_modulus,
+                dst_carry_modulus,
+            )
+            .map_err(|err| format!("Cannot build casting key: {err}"))?;
+
+            if cast_rshift != expected_cast_rshift {
+                return Err(format!(
+                    "Mismatch between the provided cast_rshift ({cast_rshift}) and the \
+                    cast_rshift computed from the source and destination server key moduli \
+                    ({expected_cast_rshift})",
+                ));
+            }
+        }
+        None => {
+            if cast_rshift < 0 {
+                return Err("Trying to build a casting key with a negative cast_rshift \
+                    without providing a source server key, this is not supported"
+                    .to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Versionize)]
 #[versionize(KeySwitchingKeyMaterialVersions)]
 pub struct KeySwitchingKeyMaterial {
@@ -36,6 +168,10 @@ pub struct KeySwitchingKeyMaterial {
     pub(crate) destination_key: EncryptionKeyChoice,
 }
 
+impl crate::named::Named for KeySwitchingKeyMaterial {
+    const NAME: &'static str = "shortint::KeySwitchingKeyMaterial";
+}
+
 impl KeySwitchingKeyMaterial {
     pub fn into_raw_parts(self) -> (LweKeyswitchKeyOwned<u64>, i8, EncryptionKeyChoice) {
         let Self {
@@ -67,6 +203,22 @@ impl KeySwitchingKeyMaterial {
     }
 }
 
+impl<'key> KeySwitchingKeyMaterialView<'key> {
+    /// Construct a [`KeySwitchingKeyMaterialView`] from its constituents, see
+    /// [`KeySwitchingKeyView::try_from_raw_parts`] to check them against the server keys.
+    pub fn from_raw_parts(
+        key_switching_key: &'key LweKeyswitchKeyOwned<u64>,
+        cast_rshift: i8,
+        destination_key: EncryptionKeyChoice,
+    ) -> Self {
+        Self {
+            key_switching_key,
+            cast_rshift,
+            destination_key,
+        }
+    }
+}
+
 // This is used to have the ability to build a keyswitching key without owning the ServerKey
 // It is a bit of a hack, but at this point it seems ok
 pub(crate) struct KeySwitchingKeyBuildHelper<'keys> {
@@ -80,11 +232,86 @@ pub(crate) struct KeySwitchingKeyBuildHelper<'keys> {
 /// The casting key is generated by the client and is meant to be published: the client
 /// sends it to the server so it can cast from one set of parameters to another.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Versionize)]
-#[versionize(KeySwitchingKeyVersions)]
+#[versionize(convert = "SerializableKeySwitchingKey")]
 pub struct KeySwitchingKey {
     pub(crate) key_switching_key_material: KeySwitchingKeyMaterial,
     pub(crate) dest_server_key: ServerKey,
     pub(crate) src_server_key: Option<ServerKey>,
+    // Generated on the first cast, only depends on the other fields
+    #[serde(skip)]
+    pub(crate) cast_lookup_tables: CastLookupTablesCache,
+}
+
+/// The serialized form of a [`KeySwitchingKey`], without its cached [`CastLookupTables`].
+#[derive(Serialize, Deserialize, Versionize)]
+#[versionize(KeySwitchingKeyVersions)]
+pub struct SerializableKeySwitchingKey {
+    pub(crate) key_switching_key_material: KeySwitchingKeyMaterial,
+    pub(crate) dest_server_key: ServerKey,
+    pub(crate) src_server_key: Option<ServerKey>,
+}
+
+impl From<KeySwitchingKey> for SerializableKeySwitchingKey {
+    fn from(value: KeySwitchingKey) -> Self {
+        let (key_switching_key_material, dest_server_key, src_server_key) = value.into_raw_parts();
+
+        Self {
+            key_switching_key_material,
+            dest_server_key,
+            src_server_key,
+        }
+    }
+}
+
+impl From<SerializableKeySwitchingKey> for KeySwitchingKey {
+    fn from(value: SerializableKeySwitchingKey) -> Self {
+        let SerializableKeySwitchingKey {
+            key_switching_key_material,
+            dest_server_key,
+            src_server_key,
+        } = value;
+
+        Self {
+            key_switching_key_material,
+            dest_server_key,
+            src_server_key,
+            cast_lookup_tables: CastLookupTablesCache::default(),
+        }
+    }
+}
+
+/// Lazily generated [`CastLookupTables`] of a [`KeySwitchingKey`].
+///
+/// The tables are derived from the key, so they are neither serialized nor compared.
+#[derive(Clone, Default)]
+pub(crate) struct CastLookupTablesCache(OnceLock<CastLookupTables>);
+
+impl CastLookupTablesCache {
+    pub(crate) fn get(&self) -> Option<&CastLookupTables> {
+        self.0.get()
+    }
+
+    fn get_or_init(&self, f: impl FnOnce() -> CastLookupTables) -> &CastLookupTables {
+        self.0.get_or_init(f)
+    }
+}
+
+impl PartialEq for CastLookupTablesCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Debug for CastLookupTablesCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CastLookupTablesCache")
+            .field("is_initialized", &self.get().is_some())
+            .finish()
+    }
+}
+
+impl crate::named::Named for KeySwitchingKey {
+    const NAME: &'static str = "shortint::KeySwitchingKey";
 }
 
 impl<'keys> From<KeySwitchingKeyBuildHelper<'keys>> for KeySwitchingKey {
@@ -99,6 +326,7 @@ impl<'keys> From<KeySwitchingKeyBuildHelper<'keys>> for KeySwitchingKey {
             key_switching_key_material,
             dest_server_key: dest_server_key.to_owned(),
             src_server_key: src_server_key.map(ToOwned::to_owned),
+            cast_lookup_tables: CastLookupTablesCache::default(),
         }
     }
 }
@@ -115,6 +343,9 @@ pub struct KeySwitchingKeyView<'keys> {
     pub(crate) key_switching_key_material: KeySwitchingKeyMaterialView<'keys>,
     pub(crate) dest_server_key: &'keys ServerKey,
     pub(crate) src_server_key: Option<&'keys ServerKey>,
+    // Only available when viewing a KeySwitchingKey, views built from raw parts generate the
+    // lookup tables on each cast
+    pub(crate) cast_lookup_tables: Option<&'keys CastLookupTablesCache>,
 }
 
 impl<'keys> KeySwitchingKeyBuildHelper<'keys> {
@@ -133,18 +364,61 @@ impl<'keys> KeySwitchingKeyBuildHelper<'keys> {
             engine.new_key_switching_key(&input_secret_key, output_key_pair.0, params)
         });
 
+        Self::from_key_switching_key(
+            key_switching_key,
+            &input_secret_key,
+            input_key_pair.1,
+            output_key_pair,
+            params,
+        )
+    }
+
+    pub(crate) fn par_new<'input_key, InputEncryptionKey>(
+        input_key_pair: (InputEncryptionKey, Option<&'keys ServerKey>),
+        output_key_pair: (&'keys ClientKey, &'keys ServerKey),
+        params: ShortintKeySwitchingParameters,
+    ) -> Self
+    where
+        InputEncryptionKey: Into<SecretEncryptionKeyView<'input_key>>,
+    {
+        let input_secret_key: SecretEncryptionKeyView<'_> = input_key_pair.0.into();
+
+        // Creation of the key switching key
+        let key_switching_key = ShortintEngine::with_thread_local_mut(|engine| {
+            engine.par_new_key_switching_key(&input_secret_key, output_key_pair.0, params)
+        });
+
+        Self::from_key_switching_key(
+            key_switching_key,
+            &input_secret_key,
+            input_key_pair.1,
+            output_key_pair,
+            params,
+        )
+    }
+
+    fn from_key_switching_key(
+        key_switching_key: LweKeyswitchKeyOwned<u64>,
+        input_secret_key: &SecretEncryptionKeyView<'_>,
+        src_server_key: Option<&'keys ServerKey>,
+        output_key_pair: (&'keys ClientKey, &'keys ServerKey),
+        params: ShortintKeySwitchingParameters,
+    ) -> Self {
+        let cast_rshift = compute_cast_rshift(
+            input_secret_key.message_modulus,
+            input_secret_key.carry_modulus,
+            output_key_pair.0.parameters.message_modulus(),
+            output_key_pair.0.parameters.carry_modulus(),
+        )
+        .unwrap_or_else(|err| panic!("Cannot create casting key: {err}"));
+
         let full_message_modulus_input =
             input_secret_key.carry_modulus.0 * input_secret_key.message_modulus.0;
         let full_message_modulus_output = output_key_pair.0.parameters.carry_modulus().0
             * output_key_pair.0.parameters.message_modulus().0;
-        assert!(
-            full_message_modulus_input.is_power_of_two()
-                && full_message_modulus_output.is_power_of_two(),
-            "Cannot create casting key if the full messages moduli are not a power of 2"
-        );
         if full_message_modulus_input > full_message_modulus_output {
             assert!(
-                input_key_pair.1.is_some(),
+                src_server_key.is_some(),
                 "Trying to build a shortint::KeySwitchingKey \
                 going from a large modulus {full_message_modulus_input} \
                 to a smaller modulus {full_message_modulus_output} \
@@ -152,18 +426,15 @@ impl<'keys> KeySwitchingKeyBuildHelper<'keys> {
             );
         }
 
-        let nb_bits_input: i8 = full_message_modulus_input.ilog2().try_into().unwrap();
-        let nb_bits_output: i8 = full_message_modulus_output.ilog2().try_into().unwrap();
-
         // Pack the keys in the casting key set:
         Self {
             key_switching_key_material: KeySwitchingKeyMaterial {
                 key_switching_key,
-                cast_rshift: nb_bits_output - nb_bits_input,
+                cast_rshift,
                 destination_key: params.destination_key,
             },
             dest_server_key: output_key_pair.1,
-            src_server_key: input_key_pair.1,
+            src_server_key,
         }
     }
 }
@@ -203,26 +474,80 @@ impl KeySwitchingKey {
         KeySwitchingKeyBuildHelper::new(input_key_pair, output_key_pair, params).into()
     }
 
+    /// Parallel variant of [`Self::new`], the keyswitch key is generated using rayon which is
+    /// faster for large parameter sets.
+    ///
+    /// This requires the rayon path of the keyswitch key generation in `core_crypto` and uses all
+    /// threads available in the current rayon thread pool. For the same state of the random
+    /// generators the generated key is the same as the one of [`Self::new`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::shortint::parameters::{
+    ///     PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_2_CARRY_2_KS_PBS,
+    /// };
+    /// use tfhe::shortint::prelude::*;
+    /// use tfhe::shortint::{gen_keys, KeySwitchingKey};
+    ///
+    /// // Generate the client keys and server keys:
+    /// let (ck1, sk1) = gen_keys(PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64);
+    /// let (ck2, sk2) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+    ///
+    /// // Generate the server key:
+    /// let ksk = KeySwitchingKey::par_new(
+    ///     (&ck1, Some(&sk1)),
+    ///     (&ck2, &sk2),
+    ///     PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    /// );
+    ///
+    /// let ct = ck1.encrypt(1);
+    /// let cast = ksk.cast(&ct);
+    /// assert_eq!(ck2.decrypt(&cast), 1);
+    /// ```
+    pub fn par_new<'input_key, InputEncryptionKey>(
+        input_key_pair: (InputEncryptionKey, Option<&ServerKey>),
+        output_key_pair: (&ClientKey, &ServerKey),
+        params: ShortintKeySwitchingParameters,
+    ) -> Self
+    where
+        InputEncryptionKey: Into<SecretEncryptionKeyView<'input_key>>,
+    {
+        KeySwitchingKeyBuildHelper::par_new(input_key_pair, output_key_pair, params).into()
+    }
+
     pub fn as_view(&self) -> KeySwitchingKeyView<'_> {
         let Self {
             key_switching_key_material,
             dest_server_key,
             src_server_key,
+            cast_lookup_tables,
         } = self;
 
         KeySwitchingKeyView {
             key_switching_key_material: key_switching_key_material.as_view(),
             dest_server_key,
             src_server_key: src_server_key.as_ref(),
+            cast_lookup_tables: Some(cast_lookup_tables),
         }
     }
 
+    /// Return the [`KeySwitchingKeyMaterial`] of this key.
+    ///
+    /// The material does not embed the server keys, serializing it instead of the full
+    /// [`KeySwitchingKey`] avoids sending keys the server already has. The key can then be rebuilt
+    /// with [`Self::from_raw_parts`] or borrowed with [`KeySwitchingKeyView::from_raw_parts`].
+    pub fn key_switching_key_material(&self) -> &KeySwitchingKeyMaterial {
+        &self.key_switching_key_material
+    }
+
     /// Deconstruct a [`KeySwitchingKey`] into its constituents.
     pub fn into_raw_parts(self) -> (KeySwitchingKeyMaterial, ServerKey, Option<ServerKey>) {
         let Self {
             key_switching_key_material,
             dest_server_key,
             src_server_key,
+            cast_lookup_tables: _,
         } = self;
 
         (key_switching_key_material, dest_server_key, src_server_key)
@@ -232,92 +557,154 @@ impl KeySwitchingKey {
     ///
     /// # Panics
     ///
-    /// Panics if the provided raw parts are not compatible with each other, i.e.:
+    /// Panics if the provided raw parts are not compatible with each other, see
+    /// [`KeySwitchingKeyView::try_from_raw_parts`] for the checks done.
     ///
-    /// if the provided source [`ServerKey`] ciphertext
-    /// [`LweDimension`](`crate::core_crypto::commons::parameters::LweDimension`) does not match the
-    /// input [`LweDimension`](`crate::core_crypto::commons::parameters::LweDimension`) of the
-    /// [`LweKeyswitchKeyOwned`] in the provided [`KeySwitchingKeyMaterial`] or if the provided
-    /// destination [`ServerKey`] ciphertext
-    /// [`LweDimension`](`crate::core_crypto::commons::parameters::LweDimension`) does not match
-    /// the output [`LweDimension`](`crate::core_crypto::commons::parameters::LweDimension`) of
-    /// the [`LweKeyswitchKeyOwned`] in the provided [`KeySwitchingKeyMaterial`].
+    /// The `destination_key` of the [`KeySwitchingKeyMaterial`] does not have to match the
+    /// [`PBSOrder`] of the destination [`ServerKey`], see [`DestinationKeyAdjustment`] for the
+    /// cost of each combination.
     pub fn from_raw_parts(
         key_switching_key_material: KeySwitchingKeyMaterial,
         dest_server_key: ServerKey,
         src_server_key: Option<ServerKey>,
     ) -> Self {
-        match src_server_key {
-            Some(ref src_server_key) => {
-                let src_lwe_dimension = src_server_key.ciphertext_lwe_dimension();
-
-                assert_eq!(
-                    src_lwe_dimension,
-                    key_switching_key_material
-                        .key_switching_key
-                        .input_key_lwe_dimension(),
-                    "Mismatch between the source ServerKey ciphertext LweDimension ({:?}) \
-                    and the LweKeyswitchKey input LweDimension ({:?})",
-                    src_lwe_dimension,
-                    key_switching_key_material
-                        .key_switching_key
-                        .input_key_lwe_dimension(),
-                );
+        KeySwitchingKeyView::try_from_raw_parts(
+            key_switching_key_material.as_view(),
+            &dest_server_key,
+            src_server_key.as_ref(),
+        )
+        .unwrap_or_else(|err| panic!("{err}"));
 
-                assert_eq!(
-                    src_server_key.ciphertext_modulus, dest_server_key.ciphertext_modulus,
-                    "Mismatch between the source ServerKey CiphertextModulus ({:?}) \
-                    and the destination ServerKey CiphertextModulus ({:?})",
-                    src_server_key.ciphertext_modulus, dest_server_key.ciphertext_modulus,
-                );
-            }
-            None => assert!(
-                key_switching_key_material.cast_rshift >= 0,
-                "Trying to build a shortint::KeySwitchingKey with a negative cast_rshift \
-                without providing a source ServerKey, this is not supported"
-            ),
+        Self {
+            key_switching_key_material,
+            dest_server_key,
+            src_server_key,
+            cast_lookup_tables: CastLookupTablesCache::default(),
         }
+    }
 
-        let dst_lwe_dimension = match key_switching_key_material.destination_key {
+    /// Construct a [`KeySwitchingKey`] from an already generated [`LweKeyswitchKeyOwned`].
+    ///
+    /// The `cast_rshift` is derived from the moduli of the source and destination [`ServerKey`]
+    /// and the `destination_key` is taken from `params`.
+    ///
+    /// Returns an error if the moduli are not compatible (see [`compute_cast_rshift`]) or if the
+    /// keyswitch key does not match the provided server keys or parameters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::shortint::parameters::{
+    ///     PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_2_CARRY_2_KS_PBS,
+    /// };
+    /// use tfhe::shortint::prelude::*;
+    /// use tfhe::shortint::{gen_keys, KeySwitchingKey};
+    ///
+    /// let (ck1, sk1) = gen_keys(PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64);
+    /// let (ck2, sk2) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+    ///
+    /// // Keyswitch key generated elsewhere, here extracted from a casting key
+    /// let (material, _, _) = KeySwitchingKey::new(
+    ///     (&ck1, Some(&sk1)),
+    ///     (&ck2, &sk2),
+    ///     PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    /// )
+    /// .into_raw_parts();
+    /// let (lwe_ksk, _, _) = material.into_raw_parts();
+    ///
+    /// let ksk = KeySwitchingKey::from_existing_ksk(
+    ///     lwe_ksk,
+    ///     sk2,
+    ///     sk1,
+    ///     PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    /// )
+    /// .unwrap();
+    ///
+    /// let ct = ck1.encrypt(1);
+    /// let cast = ksk.cast(&ct);
+    /// assert_eq!(ck2.decrypt(&cast), 1);
+    /// ```
+    pub fn from_existing_ksk(
+        key_switching_key: LweKeyswitchKeyOwned<u64>,
+        dest_server_key: ServerKey,
+        src_server_key: ServerKey,
+        params: ShortintKeySwitchingParameters,
+    ) -> Result<Self, crate::Error> {
+        let cast_rshift = compute_cast_rshift(
+            src_server_key.message_modulus,
+            src_server_key.carry_modulus,
+            dest_server_key.message_modulus,
+            dest_server_key.carry_modulus,
+        )
+        .map_err(|err| crate::Error::new(format!("{err}")))?;
+
+        if key_switching_key.decomposition_base_log() != params.ks_base_log
+            || key_switching_key.decomposition_level_count() != params.ks_level
+        {
+            return Err(crate::Error::new(format!(
+                "Mismatch between the LweKeyswitchKey decomposition ({:?}, {:?}) \
+                and the parameters decomposition ({:?}, {:?})",
+                key_switching_key.decomposition_base_log(),
+                key_switching_key.decomposition_level_count(),
+                params.ks_base_log,
+                params.ks_level,
+            )));
+        }
+
+        let src_lwe_dimension = src_server_key.ciphertext_lwe_dimension();
+        if key_switching_key.input_key_lwe_dimension() != src_lwe_dimension {
+            return Err(crate::Error::new(format!(
+                "Mismatch between the source ServerKey ciphertext LweDimension ({:?}) \
+                and the LweKeyswitchKey input LweDimension ({:?})",
+                src_lwe_dimension,
+                key_switching_key.input_key_lwe_dimension(),
+            )));
+        }
+
+        let dst_lwe_dimension = match params.destination_key {
             EncryptionKeyChoice::Big => dest_server_key.bootstrapping_key.output_lwe_dimension(),
             EncryptionKeyChoice::Small => dest_server_key.bootstrapping_key.input_lwe_dimension(),
         };
+        if key_switching_key.output_key_lwe_dimension() != dst_lwe_dimension {
+            return Err(crate::Error::new(format!(
+                "Mismatch between the destination ServerKey ciphertext LweDimension ({:?}) \
+                and the LweKeyswitchKey output LweDimension ({:?})",
+                dst_lwe_dimension,
+                key_switching_key.output_key_lwe_dimension(),
+            )));
+        }
 
-        assert_eq!(
-            dst_lwe_dimension,
-            key_switching_key_material
-                .key_switching_key
-                .output_key_lwe_dimension(),
-            "Mismatch between the destination ServerKey ciphertext LweDimension ({:?}) \
-            and the LweKeyswitchKey output LweDimension ({:?})",
-            dst_lwe_dimension,
-            key_switching_key_material
-                .key_switching_key
-                .output_key_lwe_dimension(),
-        );
-        assert_eq!(
-            key_switching_key_material
-                .key_switching_key
-                .ciphertext_modulus(),
-            dest_server_key.ciphertext_modulus,
-            "Mismatch between the LweKeyswitchKey CiphertextModulus ({:?}) \
-            and the destination ServerKey CiphertextModulus ({:?})",
-            key_switching_key_material
-                .key_switching_key
-                .ciphertext_modulus(),
-            dest_server_key.ciphertext_modulus,
-        );
+        if key_switching_key.ciphertext_modulus() != dest_server_key.ciphertext_modulus
+            || src_server_key.ciphertext_modulus != dest_server_key.ciphertext_modulus
+        {
+            return Err(crate::Error::new(format!(
+                "Mismatch between the CiphertextModulus of the LweKeyswitchKey ({:?}), \
+                the source ServerKey ({:?}) and the destination ServerKey ({:?})",
+                key_switching_key.ciphertext_modulus(),
+                src_server_key.ciphertext_modulus,
+                dest_server_key.ciphertext_modulus,
+            )));
+        }
 
-        Self {
-            key_switching_key_material,
+        Ok(Self {
+            key_switching_key_material: KeySwitchingKeyMaterial {
+                key_switching_key,
+                cast_rshift,
+                destination_key: params.destination_key,
+            },
             dest_server_key,
-            src_server_key,
-        }
+            src_server_key: Some(src_server_key),
+            cast_lookup_tables: CastLookupTablesCache::default(),
+        })
     }
 
     /// Cast a ciphertext from the source parameter set to the dest parameter set,
     /// returning a new ciphertext.
     ///
+    /// The output is always refreshed by a PBS under the destination key (unless the input is
+    /// trivial) and has a [`NoiseLevel::NOMINAL`], see [`Self::cast_without_refresh`] to skip
+    /// that refresh when it is not needed.
+    ///
     /// # Example (the following code won't actually run because this function is private)
     ///
     /// ```rust
@@ -348,6 +735,202 @@ impl KeySwitchingKey {
     pub fn cast(&self, input_ct: &Ciphertext) -> Ciphertext {
         self.as_view().cast(input_ct)
     }
+
+    /// Cast a ciphertext without refreshing it under the destination key, see
+    /// [`KeySwitchingKeyView::cast_without_refresh`].
+    pub fn cast_without_refresh(&self, input_ct: &Ciphertext) -> Ciphertext {
+        self.as_view().cast_without_refresh(input_ct)
+    }
+
+    /// Return the kind of operations [`Self::cast`] runs, see
+    /// [`KeySwitchingKeyView::cast_cost_class`].
+    pub fn cast_cost_class(&self) -> CastCostClass {
+        self.as_view().cast_cost_class()
+    }
+
+    /// Return how the output of the casting keyswitch is brought under the key expected by the
+    /// destination [`ServerKey`], see [`KeySwitchingKeyView::destination_key_adjustment`].
+    pub fn destination_key_adjustment(&self) -> DestinationKeyAdjustment {
+        self.as_view().destination_key_adjustment()
+    }
+
+    /// Estimate the variance of the noise of a ciphertext cast without the final refresh, see
+    /// [`KeySwitchingKeyView::estimate_output_variance`].
+    pub fn estimate_output_variance(&self, input_variance: Variance) -> Variance {
+        self.as_view().estimate_output_variance(input_variance)
+    }
+
+    /// Estimate the variance of the noise of a ciphertext returned by [`Self::cast`], see
+    /// [`KeySwitchingKeyView::estimate_refreshed_output_variance`].
+    pub fn estimate_refreshed_output_variance(&self) -> Variance {
+        self.as_view().estimate_refreshed_output_variance()
+    }
+
+    /// Return the [`LweDimension`] of the key the casting keyswitch key switches from.
+    pub fn input_key_lwe_dimension(&self) -> LweDimension {
+        self.key_switching_key_material
+            .key_switching_key
+            .input_key_lwe_dimension()
+    }
+
+    /// Return the [`LweDimension`] of the key the casting keyswitch key switches to.
+    pub fn output_key_lwe_dimension(&self) -> LweDimension {
+        self.key_switching_key_material
+            .key_switching_key
+            .output_key_lwe_dimension()
+    }
+
+    /// Return the [`DecompositionBaseLog`] of the casting keyswitch key.
+    pub fn decomposition_base_log(&self) -> DecompositionBaseLog {
+        self.key_switching_key_material
+            .key_switching_key
+            .decomposition_base_log()
+    }
+
+    /// Return the [`DecompositionLevelCount`] of the casting keyswitch key.
+    pub fn decomposition_level_count(&self) -> DecompositionLevelCount {
+        self.key_switching_key_material
+            .key_switching_key
+            .decomposition_level_count()
+    }
+
+    /// Generate the lookup tables used by [`Self::cast`], see
+    /// [`KeySwitchingKeyView::generate_cast_lookup_tables`].
+    pub fn generate_cast_lookup_tables(&self) -> CastLookupTables {
+        self.as_view().generate_cast_lookup_tables()
+    }
+
+    /// Return the lookup tables used by [`Self::cast`].
+    ///
+    /// They are generated on the first call (or the first cast) and stored in the key, so they
+    /// are generated at most once per key. They are not serialized.
+    pub fn cast_lookup_tables(&self) -> &CastLookupTables {
+        self.cast_lookup_tables
+            .get_or_init(|| self.as_view().generate_cast_lookup_tables())
+    }
+
+    /// Generate the lookup tables used by [`Self::cast_with_rounding`], see
+    /// [`KeySwitchingKeyView::generate_cast_lookup_tables_with_rounding`].
+    pub fn generate_cast_lookup_tables_with_rounding(
+        &self,
+        rounding: CastRounding,
+    ) -> CastLookupTables {
+        self.as_view()
+            .generate_cast_lookup_tables_with_rounding(rounding)
+    }
+
+    /// Cast a ciphertext rounding the low bits dropped when casting to a bigger message modulus,
+    /// see [`KeySwitchingKeyView::cast_with_rounding`].
+    pub fn cast_with_rounding(&self, input_ct: &Ciphertext, rounding: CastRounding) -> Ciphertext {
+        self.as_view().cast_with_rounding(input_ct, rounding)
+    }
+
+    /// Cast a ciphertext reusing precomputed lookup tables, see
+    /// [`KeySwitchingKeyView::cast_with_lookup_tables`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::shortint::parameters::{
+    ///     PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_2_CARRY_2_KS_PBS,
+    /// };
+    /// use tfhe::shortint::prelude::*;
+    /// use tfhe::shortint::{gen_keys, KeySwitchingKey};
+    ///
+    /// // Generate the client keys and server keys:
+    /// let (ck1, sk1) = gen_keys(PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64);
+    /// let (ck2, sk2) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+    ///
+    /// // Generate the server key:
+    /// let ksk = KeySwitchingKey::new(
+    ///     (&ck1, Some(&sk1)),
+    ///     (&ck2, &sk2),
+    ///     PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    /// );
+    ///
+    /// // The lookup tables are generated once for all the casts
+    /// let luts = ksk.generate_cast_lookup_tables();
+    ///
+    /// for cleartext in 0..2 {
+    ///     let cipher = ck1.encrypt(cleartext);
+    ///     let cipher_2 = ksk.cast_with_lookup_tables(&cipher, &luts);
+    ///
+    ///     assert_eq!(ck2.decrypt(&cipher_2), cleartext);
+    /// }
+    /// ```
+    pub fn cast_with_lookup_tables(
+        &self,
+        input_ct: &Ciphertext,
+        lookup_tables: &CastLookupTables,
+    ) -> Ciphertext {
+        self.as_view()
+            .cast_with_lookup_tables(input_ct, lookup_tables)
+    }
+
+    /// Decompress a ciphertext and cast it reusing precomputed lookup tables, see
+    /// [`KeySwitchingKeyView::unpack_and_cast_with_lookup_tables`].
+    pub fn unpack_and_cast_with_lookup_tables(
+        &self,
+        packed: &CompressedCiphertextList,
+        index: usize,
+        decompression_key: &DecompressionKey,
+        lookup_tables: &CastLookupTables,
+    ) -> Option<Ciphertext> {
+        self.as_view().unpack_and_cast_with_lookup_tables(
+            packed,
+            index,
+            decompression_key,
+            lookup_tables,
+        )
+    }
+
+    /// Chain this [`KeySwitchingKey`] with another one casting from the destination of `self`,
+    /// see [`ComposedKeySwitchingKey`].
+    ///
+    /// The returned key borrows both keys. Returns an error naming the mismatch if the source of
+    /// `other` is not the destination of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::shortint::parameters::{
+    ///     ShortintKeySwitchingParameters, PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+    ///     PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
+    /// };
+    /// use tfhe::shortint::prelude::*;
+    /// use tfhe::shortint::{gen_keys, KeySwitchingKey};
+    ///
+    /// let (ck1, sk1) = gen_keys(PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64);
+    /// let (ck2, sk2) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64);
+    /// let (ck3, sk3) = gen_keys(PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64);
+    ///
+    /// let ksk_1_to_2 = KeySwitchingKey::new(
+    ///     (&ck1, Some(&sk1)),
+    ///     (&ck2, &sk2),
+    ///     PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    /// );
+    /// let ksk_2_to_3 = KeySwitchingKey::new(
+    ///     (&ck2, Some(&sk2)),
+    ///     (&ck3, &sk3),
+    ///     ShortintKeySwitchingParameters::new(
+    ///         PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64.ks_base_log,
+    ///         PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64.ks_level,
+    ///         PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64.encryption_key_choice,
+    ///     ),
+    /// );
+    ///
+    /// let ksk_1_to_3 = ksk_1_to_2.compose(&ksk_2_to_3).unwrap();
+    ///
+    /// let ct = ck1.encrypt(1);
+    /// let cast = ksk_1_to_3.cast(&ct);
+    /// assert_eq!(ck3.decrypt(&cast), 1);
+    /// ```
+    pub fn compose<'keys>(
+        &'keys self,
+        other: &'keys Self,
+    ) -> Result<ComposedKeySwitchingKey<'keys>, ComposeError> {
+        ComposedKeySwitchingKey::new(self, other)
+    }
 }
 
 impl<'keys> KeySwitchingKeyView<'keys> {
@@ -363,6 +946,7 @@ impl<'keys> KeySwitchingKeyView<'keys> {
             key_switching_key_material,
             dest_server_key,
             src_server_key,
+            cast_lookup_tables: _,
         } = self;
 
         (key_switching_key_material, dest_server_key, src_server_key)
@@ -372,7 +956,19 @@ impl<'keys> KeySwitchingKeyView<'keys> {
     ///
     /// # Panics
     ///
-    /// Panics if the provided raw parts are not compatible with each other, i.e.:
+    /// Panics if the provided raw parts are not compatible with each other, see
+    /// [`Self::try_from_raw_parts`] for the checks done and for a non panicking version.
+    pub fn from_raw_parts(
+        key_switching_key_material: KeySwitchingKeyMaterialView<'keys>,
+        dest_server_key: &'keys ServerKey,
+        src_server_key: Option<&'keys ServerKey>,
+    ) -> Self {
+        Self::try_from_raw_parts(key_switching_key_material, dest_server_key, src_server_key)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Construct a [`KeySwitchingKeyView`] from its constituents, returning an error if they are
+    /// not compatible with each other, i.e.:
     ///
     /// if the provided source [`ServerKey`] ciphertext
     /// [`LweDimension`](`crate::core_crypto::commons::parameters::LweDimension`) does not match the
@@ -382,146 +978,591 @@ impl<'keys> KeySwitchingKeyView<'keys> {
     /// [`LweDimension`](`crate::core_crypto::commons::parameters::LweDimension`) does not match
     /// the output [`LweDimension`](`crate::core_crypto::commons::parameters::LweDimension`) of
     /// the [`LweKeyswitchKeyOwned`] in the provided [`KeySwitchingKeyMaterial`].
-    pub fn from_raw_parts(
+    ///
+    /// An error is also returned if a source server key is provided and the `cast_rshift` of the
+    /// provided [`KeySwitchingKeyMaterial`] is not the one computed by [`compute_cast_rshift`]
+    /// from the source and destination moduli, or if no source server key is provided and the
+    /// `cast_rshift` is negative.
+    ///
+    /// The `destination_key` of the [`KeySwitchingKeyMaterial`] does not have to match the
+    /// [`PBSOrder`] of the destination [`ServerKey`], see [`DestinationKeyAdjustment`] for the
+    /// cost of each combination.
+    ///
+    /// This is meant for parts received or deserialized independently, where a mismatch should
+    /// be handled rather than abort.
+    pub fn try_from_raw_parts(
         key_switching_key_material: KeySwitchingKeyMaterialView<'keys>,
         dest_server_key: &'keys ServerKey,
         src_server_key: Option<&'keys ServerKey>,
-    ) -> Self {
-        match src_server_key {
-            Some(src_server_key) => {
-                let src_lwe_dimension = src_server_key.ciphertext_lwe_dimension();
-
-                assert_eq!(
-                    src_lwe_dimension,
-                    key_switching_key_material
-                        .key_switching_key
-                        .input_key_lwe_dimension(),
+    ) -> Result<Self, crate::Error> {
+        let ksk_input_lwe_dimension = key_switching_key_material
+            .key_switching_key
+            .input_key_lwe_dimension();
+        let ksk_output_lwe_dimension = key_switching_key_material
+            .key_switching_key
+            .output_key_lwe_dimension();
+        let ksk_ciphertext_modulus = key_switching_key_material
+            .key_switching_key
+            .ciphertext_modulus();
+
+        if let Some(src_server_key) = src_server_key {
+            let src_lwe_dimension = src_server_key.ciphertext_lwe_dimension();
+
+            if src_lwe_dimension != ksk_input_lwe_dimension {
+                return Err(crate::Error::new(format!(
                     "Mismatch between the source ServerKey ciphertext LweDimension ({:?}) \
                     and the LweKeyswitchKey input LweDimension ({:?})",
-                    src_lwe_dimension,
-                    key_switching_key_material
-                        .key_switching_key
-                        .input_key_lwe_dimension(),
-                );
+                    src_lwe_dimension, ksk_input_lwe_dimension,
+                )));
+            }
+
+            if src_server_key.ciphertext_modulus != dest_server_key.ciphertext_modulus {
+                return Err(crate::Error::new(format!(
+                    "Mismatch between the source ServerKey CiphertextModulus ({:?}) \
+                    and the destination ServerKey CiphertextModulus ({:?})",
+                    src_server_key.ciphertext_modulus, dest_server_key.ciphertext_modulus,
+                )));
+            }
+        }
+
+        check_cast_rshift(
+            src_server_key.map(|key| (key.message_modulus, key.carry_modulus)),
+            (
+                dest_server_key.message_modulus,
+                dest_server_key.carry_modulus,
+            ),
+            key_switching_key_material.cast_rshift,
+        )
+        .map_err(crate::Error::new)?;
+
+        let dst_lwe_dimension = match key_switching_key_material.destination_key {
+            EncryptionKeyChoice::Big => dest_server_key.bootstrapping_key.output_lwe_dimension(),
+            EncryptionKeyChoice::Small => dest_server_key.bootstrapping_key.input_lwe_dimension(),
+        };
+
+        if dst_lwe_dimension != ksk_output_lwe_dimension {
+            return Err(crate::Error::new(format!(
+                "Mismatch between the destination ServerKey ciphertext LweDimension ({:?}) \
+                and the LweKeyswitchKey output LweDimension ({:?})",
+                dst_lwe_dimension, ksk_output_lwe_dimension,
+            )));
+        }
+
+        if ksk_ciphertext_modulus != dest_server_key.ciphertext_modulus {
+            return Err(crate::Error::new(format!(
+                "Mismatch between the LweKeyswitchKey CiphertextModulus ({:?}) \
+                and the destination ServerKey CiphertextModulus ({:?})",
+                ksk_ciphertext_modulus, dest_server_key.ciphertext_modulus,
+            )));
+        }
+
+        Ok(Self {
+            key_switching_key_material,
+            dest_server_key,
+            src_server_key,
+            cast_lookup_tables: None,
+        })
+    }
+
+    /// Cast a ciphertext from the source parameter set to the dest parameter set,
+    /// returning a new ciphertext.
+    ///
+    /// The output is always refreshed by a PBS under the destination key (unless the input is
+    /// trivial) and has a [`NoiseLevel::NOMINAL`], see [`Self::cast_without_refresh`] to skip
+    /// that refresh when it is not needed.
+    ///
+    /// When casting to a bigger modulus the numeric value, carries included, is preserved: the
+    /// input encoding has a bigger scaling factor than the destination one so the keyswitched
+    /// value reads as the input value left shifted by the cast shift, which the refresh undoes.
+    /// When casting to a smaller modulus the bits that do not fit in the destination message and
+    /// carry space are dropped.
+    ///
+    /// # Example (the following code won't actually run because this function is private)
+    ///
+    /// ```rust
+    /// use tfhe::shortint::parameters::{
+    ///     PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_2_CARRY_2_KS_PBS,
+    /// };
+    /// use tfhe::shortint::prelude::*;
+    /// use tfhe::shortint::{gen_keys, KeySwitchingKey};
+    ///
+    /// // Generate the client keys and server keys:
+    /// let (ck1, sk1) = gen_keys(PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64);
+    /// let (ck2, sk2) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS);
+    ///
+    /// // Generate the server key:
+    /// let ksk = KeySwitchingKey::new(
+    ///     (&ck1, Some(&sk1)),
+    ///     (&ck2, &sk2),
+    ///     PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    /// );
+    ///
+    /// let cleartext = 1;
+    ///
+    /// let cipher = ck1.encrypt(cleartext);
+    /// let cipher_2 = ksk.cast(&cipher);
+    ///
+    /// assert_eq!(ck2.decrypt(&cipher_2), cleartext);
+    /// ```
+    pub fn cast(&self, input_ct: &Ciphertext) -> Ciphertext {
+        let res = self.cast_and_apply_functions(input_ct, None);
+        assert_eq!(res.len(), 1);
+        res.into_iter().next().unwrap()
+    }
+
+    /// Cast a ciphertext from the source parameter set to the dest parameter set,
+    /// returning a new ciphertext.
+    ///
+    /// If None is provided then an identity function is used and tighter degrees are used where
+    /// applicable.
+    pub fn cast_and_apply_functions(
+        &self,
+        input_ct: &Ciphertext,
+        functions: Option<&[&(dyn Fn(u64) -> u64 + Sync)]>,
+    ) -> Vec<Ciphertext> {
+        if let Some(value) = self.trivial_cast_value(input_ct) {
+            return match functions {
+                Some(functions) => functions
+                    .iter()
+                    .map(|function| {
+                        self.dest_server_key
+                            .unchecked_create_trivial(function(value))
+                    })
+                    .collect(),
+                None => vec![self.dest_server_key.unchecked_create_trivial(value)],
+            };
+        }
+
+        match functions {
+            Some(functions) => {
+                let pre_processing_lut = self.pre_processing_lookup_table();
+                let cast_rshift = self.key_switching_key_material.cast_rshift;
+                let post_processing_luts: Vec<_> = functions
+                    .par_iter()
+                    .map(|function| {
+                        if cast_rshift > 0 {
+                            // Call the function on the shifted arrival value
+                            self.dest_server_key
+                                .generate_lookup_table(|n| function(n >> cast_rshift))
+                        } else {
+                            self.dest_server_key.generate_lookup_table(function)
+                        }
+                    })
+                    .collect();
+
+                let res = self.keyswitch_to_destination(input_ct, pre_processing_lut.as_deref());
+
+                self.cast_with_lookup_tables_impl(res, &post_processing_luts, true)
+            }
+            None => {
+                let lookup_tables = self.cast_lookup_tables();
+                vec![self.cast_with_lookup_tables(input_ct, &lookup_tables)]
+            }
+        }
+    }
+
+    /// Return the lookup tables used by [`Self::cast`].
+    ///
+    /// When the view was obtained with [`KeySwitchingKey::as_view`] the tables are generated on
+    /// the first call and stored in the [`KeySwitchingKey`], later calls borrow them. Views built
+    /// with [`Self::from_raw_parts`] have nowhere to store them and generate them on each call.
+    pub fn cast_lookup_tables(&self) -> Cow<'keys, CastLookupTables> {
+        match self.cast_lookup_tables {
+            Some(cache) => Cow::Borrowed(cache.get_or_init(|| self.generate_cast_lookup_tables())),
+            None => Cow::Owned(self.generate_cast_lookup_tables()),
+        }
+    }
+
+    /// Same as [`Self::cast_lookup_tables`] but only for the pre-processing lookup table, which
+    /// avoids generating the identity lookup table for views without cached tables.
+    fn pre_processing_lookup_table(&self) -> Option<Cow<'keys, LookupTableOwned>> {
+        match self.cast_lookup_tables {
+            Some(cache) => cache
+                .get_or_init(|| self.generate_cast_lookup_tables())
+                .pre_processing
+                .as_ref()
+                .map(Cow::Borrowed),
+            None => self.generate_pre_processing_lookup_table().map(Cow::Owned),
+        }
+    }
+
+    /// Generate the lookup tables used by [`Self::cast`].
+    ///
+    /// These only depend on the keys, so when casting many ciphertexts with the same key they can
+    /// be generated once and passed to [`Self::cast_with_lookup_tables`].
+    pub fn generate_cast_lookup_tables(&self) -> CastLookupTables {
+        self.generate_cast_lookup_tables_with_rounding(CastRounding::Truncate)
+    }
+
+    /// Generate the lookup tables used by [`Self::cast_with_rounding`].
+    ///
+    /// The `rounding` only changes the lookup tables when casting to a bigger message modulus, see
+    /// [`CastRounding`].
+    pub fn generate_cast_lookup_tables_with_rounding(
+        &self,
+        rounding: CastRounding,
+    ) -> CastLookupTables {
+        let cast_rshift = self.key_switching_key_material.cast_rshift;
+
+        let post_processing = if cast_rshift > 0 {
+            match rounding {
+                CastRounding::Truncate => Arc::new(
+                    self.dest_server_key
+                        .generate_lookup_table(|n| n >> cast_rshift),
+                ),
+                CastRounding::Nearest => {
+                    let full_message_modulus_output = (self.dest_server_key.message_modulus.0
+                        * self.dest_server_key.carry_modulus.0)
+                        as u64;
+                    // The source message and carry space is cast_rshift bits smaller
+                    let max_input_value = (full_message_modulus_output >> cast_rshift) - 1;
+                    Arc::new(self.dest_server_key.generate_lookup_table(|n| {
+                        ((n + (1 << (cast_rshift - 1))) >> cast_rshift).min(max_input_value)
+                    }))
+                }
+            }
+        } else {
+            // The identity lookup table only depends on the destination server key, it is shared
+            // with the other keys casting to it
+            ShortintEngine::with_thread_local_mut(|engine| {
+                engine.identity_lookup_table(self.dest_server_key)
+            })
+        };
+
+        CastLookupTables {
+            pre_processing: self.generate_pre_processing_lookup_table(),
+            post_processing,
+            cast_rshift,
+        }
+    }
+
+    /// Cast a ciphertext from the source parameter set to the dest parameter set, rounding the low
+    /// bits dropped when casting to a bigger message modulus according to `rounding`.
+    ///
+    /// With [`CastRounding::Truncate`] this is the same as [`Self::cast`].
+    pub fn cast_with_rounding(&self, input_ct: &Ciphertext, rounding: CastRounding) -> Ciphertext {
+        match rounding {
+            CastRounding::Truncate => self.cast(input_ct),
+            CastRounding::Nearest => {
+                let lookup_tables = self.generate_cast_lookup_tables_with_rounding(rounding);
+                self.cast_with_lookup_tables(input_ct, &lookup_tables)
+            }
+        }
+    }
+
+    /// Cast a ciphertext from the source parameter set to the dest parameter set, using lookup
+    /// tables generated beforehand by [`Self::generate_cast_lookup_tables`].
+    ///
+    /// The result is the same as the one returned by [`Self::cast`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lookup tables were generated for a key with a different cast shift.
+    pub fn cast_with_lookup_tables(
+        &self,
+        input_ct: &Ciphertext,
+        lookup_tables: &CastLookupTables,
+    ) -> Ciphertext {
+        assert_eq!(
+            lookup_tables.cast_rshift, self.key_switching_key_material.cast_rshift,
+            "The provided CastLookupTables were not generated for this KeySwitchingKey"
+        );
+
+        if let Some(value) = self.trivial_cast_value(input_ct) {
+            return self.dest_server_key.unchecked_create_trivial(value);
+        }
+
+        let res = self.keyswitch_to_destination(input_ct, lookup_tables.pre_processing.as_ref());
+
+        let res = self.cast_with_lookup_tables_impl(
+            res,
+            core::slice::from_ref(lookup_tables.post_processing.as_ref()),
+            false,
+        );
+        assert_eq!(res.len(), 1);
+        res.into_iter().next().unwrap()
+    }
+
+    /// Decompress the ciphertext at `index` in `packed` and cast it, using lookup tables
+    /// generated beforehand by [`Self::generate_cast_lookup_tables`].
+    ///
+    /// The result decrypts to the same value as [`DecompressionKey::unpack`] followed by
+    /// [`Self::cast_with_lookup_tables`]. When casting to a smaller modulus the left shift done
+    /// on the source key before the casting keyswitch is merged in the decompression PBS, which
+    /// saves a PBS. The source server key is then not used, so it does not need to be provided.
+    ///
+    /// The `decompression_key` must have been generated from the source client key, so that the
+    /// decompressed ciphertexts are under the input key of the casting keyswitch.
+    ///
+    /// Returns `None` if `index` is out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lookup tables were generated for a key with a different cast shift.
+    pub fn unpack_and_cast_with_lookup_tables(
+        &self,
+        packed: &CompressedCiphertextList,
+        index: usize,
+        decompression_key: &DecompressionKey,
+        lookup_tables: &CastLookupTables,
+    ) -> Option<Ciphertext> {
+        assert_eq!(
+            lookup_tables.cast_rshift, self.key_switching_key_material.cast_rshift,
+            "The provided CastLookupTables were not generated for this KeySwitchingKey"
+        );
+
+        let cast_rshift = self.key_switching_key_material.cast_rshift;
 
-                assert_eq!(
-                    src_server_key.ciphertext_modulus, dest_server_key.ciphertext_modulus,
-                    "Mismatch between the source ServerKey CiphertextModulus ({:?}) \
-                    and the destination ServerKey CiphertextModulus ({:?})",
-                    src_server_key.ciphertext_modulus, dest_server_key.ciphertext_modulus,
-                );
-            }
-            None => assert!(
-                key_switching_key_material.cast_rshift >= 0,
-                "Trying to build a shortint::KeySwitchingKey with a negative cast_rshift \
-                without providing a source ServerKey, this is not supported"
-            ),
+        if cast_rshift >= 0 {
+            let unpacked = decompression_key.unpack(packed, index)?;
+            return Some(self.cast_with_lookup_tables(&unpacked, lookup_tables));
         }
 
-        let dst_lwe_dimension = match key_switching_key_material.destination_key {
-            EncryptionKeyChoice::Big => dest_server_key.bootstrapping_key.output_lwe_dimension(),
-            EncryptionKeyChoice::Small => dest_server_key.bootstrapping_key.input_lwe_dimension(),
-        };
+        let mod_switched = decompression_key.unpack_mod_switched(packed, index)?;
 
-        assert_eq!(
-            dst_lwe_dimension,
-            key_switching_key_material
-                .key_switching_key
-                .output_key_lwe_dimension(),
-            "Mismatch between the destination ServerKey ciphertext LweDimension ({:?}) \
-            and the LweKeyswitchKey output LweDimension ({:?})",
-            dst_lwe_dimension,
-            key_switching_key_material
-                .key_switching_key
-                .output_key_lwe_dimension(),
+        // Same function as the pre-processing lookup table, see
+        // generate_pre_processing_lookup_table
+        let full_message_modulus = (packed.message_modulus.0 * packed.carry_modulus.0) as u64;
+        let pre_processed = decompression_key
+            .bootstrap_mod_switched_with_function(&mod_switched, |x| {
+                (x << -cast_rshift) % full_message_modulus
+            });
+
+        let res = self.keyswitch_pre_processed_to_destination(&pre_processed);
+
+        let res = self.cast_with_lookup_tables_impl(
+            res,
+            core::slice::from_ref(lookup_tables.post_processing.as_ref()),
+            false,
         );
-        assert_eq!(
-            key_switching_key_material
-                .key_switching_key
-                .ciphertext_modulus(),
-            dest_server_key.ciphertext_modulus,
-            "Mismatch between the LweKeyswitchKey CiphertextModulus ({:?}) \
-            and the destination ServerKey CiphertextModulus ({:?})",
-            key_switching_key_material
-                .key_switching_key
-                .ciphertext_modulus(),
-            dest_server_key.ciphertext_modulus,
+        assert_eq!(res.len(), 1);
+        res.into_iter().next()
+    }
+
+    /// If the input is a trivial ciphertext, return the value it holds once cast to the
+    /// destination parameters, the cast can then be done in the clear without any keyswitch or PBS.
+    fn trivial_cast_value(&self, input_ct: &Ciphertext) -> Option<u64> {
+        let value = input_ct.decrypt_trivial_message_and_carry().ok()?;
+
+        let full_message_modulus_input =
+            (input_ct.message_modulus.0 * input_ct.carry_modulus.0) as u64;
+        let full_message_modulus_output =
+            (self.dest_server_key.message_modulus.0 * self.dest_server_key.carry_modulus.0) as u64;
+
+        // Same as the encrypted path: when casting to a smaller modulus the bits that do not fit in
+        // the destination message and carry space are dropped
+        Some((value % full_message_modulus_input) % full_message_modulus_output)
+    }
+
+    /// Cast a ciphertext from the source parameter set to the dest parameter set, without
+    /// refreshing it under the destination key.
+    ///
+    /// Only keyswitches are applied after the pre-processing of a cast to a smaller modulus, the
+    /// output noise is therefore not the one of a fresh ciphertext and its noise level is set to
+    /// [`NoiseLevel::UNKNOWN`] so that it gets refreshed by the next PBS. Use [`Self::cast`] to
+    /// get a ciphertext with a [`NoiseLevel::NOMINAL`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cast cannot be done without a PBS under the destination key, i.e. when
+    /// casting to a bigger message modulus or when the casting key outputs ciphertexts under the
+    /// small key while the destination [`ServerKey`] expects them under the big key.
+    pub fn cast_without_refresh(&self, input_ct: &Ciphertext) -> Ciphertext {
+        if let Some(value) = self.trivial_cast_value(input_ct) {
+            return self.dest_server_key.unchecked_create_trivial(value);
+        }
+
+        let cast_rshift = self.key_switching_key_material.cast_rshift;
+
+        assert!(
+            cast_rshift <= 0,
+            "Casting to a bigger message modulus requires a PBS on the destination key, \
+            use cast instead"
         );
 
-        Self {
-            key_switching_key_material,
-            dest_server_key,
-            src_server_key,
+        let pre_processing_lut = self.pre_processing_lookup_table();
+
+        match self.keyswitch_to_destination(input_ct, pre_processing_lut.as_deref()) {
+            CastCiphertext::CorrectKey(mut ciphertext) => {
+                // Same degree as the identity cast, see cast_with_lookup_tables_impl
+                if cast_rshift < 0 {
+                    ciphertext.degree = Degree::new(ciphertext.degree.get() >> -cast_rshift);
+                }
+                ciphertext
+            }
+            CastCiphertext::WrongKeyRequiresPBS(_) => panic!(
+                "This KeySwitchingKey outputs ciphertexts under the small key while the \
+                destination ServerKey expects the big key, a PBS is required, use cast instead"
+            ),
         }
     }
 
-    /// Cast a ciphertext from the source parameter set to the dest parameter set,
-    /// returning a new ciphertext.
+    /// Return the kind of operations [`Self::cast`] runs on a non trivial input, which depends
+    /// only on the message moduli of the source and destination keys.
     ///
-    /// # Example (the following code won't actually run because this function is private)
+    /// Casting to a smaller message modulus requires an additional PBS on the source key, see
+    /// also [`Self::destination_key_adjustment`] for the operations depending on the destination
+    /// key choice.
+    ///
+    /// # Example
     ///
     /// ```rust
+    /// use tfhe::shortint::key_switching_key::CastCostClass;
     /// use tfhe::shortint::parameters::{
     ///     PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_2_CARRY_2_KS_PBS,
     /// };
     /// use tfhe::shortint::prelude::*;
     /// use tfhe::shortint::{gen_keys, KeySwitchingKey};
     ///
-    /// // Generate the client keys and server keys:
     /// let (ck1, sk1) = gen_keys(PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64);
     /// let (ck2, sk2) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS);
     ///
-    /// // Generate the server key:
     /// let ksk = KeySwitchingKey::new(
     ///     (&ck1, Some(&sk1)),
     ///     (&ck2, &sk2),
     ///     PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
     /// );
     ///
-    /// let cleartext = 1;
+    /// assert_eq!(ksk.cast_cost_class(), CastCostClass::KeyswitchThenPbs);
+    /// ```
+    pub fn cast_cost_class(&self) -> CastCostClass {
+        if self.key_switching_key_material.cast_rshift < 0 {
+            CastCostClass::PbsThenKeyswitchThenPbs
+        } else {
+            CastCostClass::KeyswitchThenPbs
+        }
+    }
+
+    /// Return how the output of the casting keyswitch is brought under the key expected by the
+    /// destination [`ServerKey`].
+    pub fn destination_key_adjustment(&self) -> DestinationKeyAdjustment {
+        let destination_pbs_order: PBSOrder =
+            self.key_switching_key_material.destination_key.into();
+
+        if destination_pbs_order == self.dest_server_key.pbs_order {
+            DestinationKeyAdjustment::None
+        } else {
+            match self.key_switching_key_material.destination_key {
+                EncryptionKeyChoice::Big => DestinationKeyAdjustment::ExtraKeyswitch,
+                EncryptionKeyChoice::Small => DestinationKeyAdjustment::PbsWithoutKeyswitch,
+            }
+        }
+    }
+
+    /// Estimate the variance of the noise of a ciphertext cast without the final refresh, i.e.
+    /// the noise of the ciphertext returned by [`Self::cast_without_refresh`] or going into the
+    /// last PBS of [`Self::cast`], given the variance of the noise of the input ciphertext.
     ///
-    /// let cipher = ck1.encrypt(cleartext);
-    /// let cipher_2 = ksk.cast(&cipher);
+    /// This accounts for the PBS on the source key of a cast to a smaller modulus, for the
+    /// casting keyswitch and for the extra keyswitch of
+    /// [`DestinationKeyAdjustment::ExtraKeyswitch`]. The variances are normalized to the torus.
     ///
-    /// assert_eq!(ck2.decrypt(&cipher_2), cleartext);
+    /// The estimate uses the [`noise_formulas`](crate::core_crypto::commons::noise_formulas)
+    /// which assume Gaussian noise distributions and keys encrypted with the minimal noise for
+    /// 132 bits of security, so it is only an approximation for other parameter sets. The PBS
+    /// estimates assume a classic (non multi bit) PBS.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::core_crypto::commons::dispersion::Variance;
+    /// use tfhe::shortint::parameters::{
+    ///     PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+    /// };
+    /// use tfhe::shortint::prelude::*;
+    /// use tfhe::shortint::{gen_keys, KeySwitchingKey};
+    ///
+    /// let (ck1, sk1) = gen_keys(PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64);
+    /// let (ck2, sk2) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64);
+    ///
+    /// let ksk = KeySwitchingKey::new(
+    ///     (&ck1, Some(&sk1)),
+    ///     (&ck2, &sk2),
+    ///     PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    /// );
+    ///
+    /// let input_variance = Variance(2.0f64.powi(-50));
+    /// let estimate = ksk.estimate_output_variance(input_variance);
+    ///
+    /// // The casting keyswitch only adds noise
+    /// assert!(estimate.0 > input_variance.0);
     /// ```
-    pub fn cast(&self, input_ct: &Ciphertext) -> Ciphertext {
-        let res = self.cast_and_apply_functions(input_ct, None);
-        assert_eq!(res.len(), 1);
-        res.into_iter().next().unwrap()
+    pub fn estimate_output_variance(&self, input_variance: Variance) -> Variance {
+        let pre_processed_variance = if self.key_switching_key_material.cast_rshift < 0 {
+            let src_server_key = self.src_server_key.as_ref().expect(
+                "No source server key in shortint::KeySwitchingKey \
+                which is required when casting to a smaller message modulus",
+            );
+            estimate_lookup_table_variance(src_server_key)
+        } else {
+            input_variance
+        };
+
+        let casting_keyswitch_variance = estimate_keyswitch_variance(
+            self.key_switching_key_material.key_switching_key,
+            self.dest_server_key.ciphertext_modulus,
+        );
+
+        let extra_keyswitch_variance = match self.destination_key_adjustment() {
+            DestinationKeyAdjustment::ExtraKeyswitch => estimate_keyswitch_variance(
+                &self.dest_server_key.key_switching_key,
+                self.dest_server_key.ciphertext_modulus,
+            ),
+            DestinationKeyAdjustment::None | DestinationKeyAdjustment::PbsWithoutKeyswitch => {
+                Variance(0.0)
+            }
+        };
+
+        Variance(
+            pre_processed_variance.0 + casting_keyswitch_variance.0 + extra_keyswitch_variance.0,
+        )
     }
 
-    /// Cast a ciphertext from the source parameter set to the dest parameter set,
-    /// returning a new ciphertext.
+    /// Estimate the variance of the noise of a ciphertext returned by [`Self::cast`], normalized
+    /// to the torus.
     ///
-    /// If None is provided then an identity function is used and tighter degrees are used where
-    /// applicable.
-    pub fn cast_and_apply_functions(
-        &self,
-        input_ct: &Ciphertext,
-        functions: Option<&[&(dyn Fn(u64) -> u64 + Sync)]>,
-    ) -> Vec<Ciphertext> {
-        let output_lwe_size = match self.key_switching_key_material.destination_key {
-            EncryptionKeyChoice::Big => self
-                .dest_server_key
-                .bootstrapping_key
-                .output_lwe_dimension()
-                .to_lwe_size(),
-            EncryptionKeyChoice::Small => self
-                .dest_server_key
-                .bootstrapping_key
-                .input_lwe_dimension()
-                .to_lwe_size(),
-        };
-        let mut keyswitched = self
-            .dest_server_key
-            .unchecked_create_trivial_with_lwe_size(0, output_lwe_size);
+    /// The output of [`Self::cast`] is refreshed under the destination key so its noise does not
+    /// depend on the input noise, as long as [`Self::estimate_output_variance`] stays small enough
+    /// for the final PBS to be correct. The same approximations as
+    /// [`Self::estimate_output_variance`] apply.
+    pub fn estimate_refreshed_output_variance(&self) -> Variance {
+        match self.destination_key_adjustment() {
+            // A raw PBS, no keyswitch after it even for a PBS_KS destination server key
+            DestinationKeyAdjustment::PbsWithoutKeyswitch => {
+                estimate_pbs_variance(self.dest_server_key)
+            }
+            DestinationKeyAdjustment::None | DestinationKeyAdjustment::ExtraKeyswitch => {
+                estimate_lookup_table_variance(self.dest_server_key)
+            }
+        }
+    }
 
-        // TODO: We are outside the standard AP, if we chain keyswitches, we will refresh, which is
-        // safer for now. We can likely add an additional flag in shortint to indicate if we
-        // want to refresh or not, for now refresh anyways.
-        keyswitched.set_noise_level(NoiseLevel::UNKNOWN);
+    /// When casting to a smaller bit length the input needs to be left shifted on the source key
+    /// before the keyswitch, this returns the lookup table doing it, if required.
+    fn generate_pre_processing_lookup_table(&self) -> Option<LookupTableOwned> {
+        let cast_rshift = self.key_switching_key_material.cast_rshift;
+
+        if cast_rshift >= 0 {
+            return None;
+        }
+
+        let src_server_key = self.src_server_key.as_ref().expect(
+            "No source server key in shortint::KeySwitchingKey \
+            which is required when casting to a smaller message modulus",
+        );
+        let full_message_modulus =
+            (src_server_key.carry_modulus.0 * src_server_key.message_modulus.0) as u64;
+        // We want to avoid the padding bit to be dirty, hence the modulus
+        Some(src_server_key.generate_lookup_table(|n| (n << -cast_rshift) % full_message_modulus))
+    }
 
+    /// Apply the pre-processing (if any) and the keyswitch of the casting key, then bring the
+    /// result under the destination server key input key when it can be done with a keyswitch.
+    fn keyswitch_to_destination(
+        &self,
+        input_ct: &Ciphertext,
+        pre_processing_lut: Option<&LookupTableOwned>,
+    ) -> CastCiphertext {
         let cast_rshift = self.key_switching_key_material.cast_rshift;
 
         // First pre process
@@ -534,18 +1575,42 @@ impl<'keys> KeySwitchingKeyView<'keys> {
                     "No source server key in shortint::KeySwitchingKey \
                     which is required when casting to a smaller message modulus",
                 );
-                // We want to avoid the padding bit to be dirty, hence the modulus
-                let acc = src_server_key.generate_lookup_table(|n| {
-                    (n << -cast_rshift)
-                        % (input_ct.carry_modulus.0 * input_ct.message_modulus.0) as u64
-                });
-                tmp_preprocessed = src_server_key.apply_lookup_table(input_ct, &acc);
+                let acc = pre_processing_lut
+                    .expect("Missing pre-processing lookup table for a cast to a smaller modulus");
+                tmp_preprocessed = src_server_key.apply_lookup_table(input_ct, acc);
                 &tmp_preprocessed
             }
             // No pre-processing
             Ordering::Equal | Ordering::Greater => input_ct,
         };
 
+        self.keyswitch_pre_processed_to_destination(pre_processed)
+    }
+
+    /// Apply the keyswitch of the casting key to a ciphertext which already went through the
+    /// pre-processing, then bring the result under the destination server key input key when it
+    /// can be done with a keyswitch.
+    fn keyswitch_pre_processed_to_destination(&self, pre_processed: &Ciphertext) -> CastCiphertext {
+        let output_lwe_size = match self.key_switching_key_material.destination_key {
+            EncryptionKeyChoice::Big => self
+                .dest_server_key
+                .bootstrapping_key
+                .output_lwe_dimension()
+                .to_lwe_size(),
+            EncryptionKeyChoice::Small => self
+                .dest_server_key
+                .bootstrapping_key
+                .input_lwe_dimension()
+                .to_lwe_size(),
+        };
+        let mut keyswitched = self
+            .dest_server_key
+            .unchecked_create_trivial_with_lwe_size(0, output_lwe_size);
+
+        // We are outside the standard AP, if we chain keyswitches the noise is not tracked anymore,
+        // cast refreshes anyways, cast_without_refresh leaves it to the caller.
+        keyswitched.set_noise_level(NoiseLevel::UNKNOWN);
+
         // The keyswitch
         keyswitch_lwe_ciphertext(
             self.key_switching_key_material.key_switching_key,
@@ -554,50 +1619,53 @@ impl<'keys> KeySwitchingKeyView<'keys> {
         );
         keyswitched.degree = pre_processed.degree;
 
-        let degree_after_keyswitch = keyswitched.degree;
-
-        enum CastCiphertext {
-            CorrectKey(Ciphertext),
-            WrongKeyRequiresPBS(Ciphertext),
-        }
-
         // Manage the destination key adjustment
-        let res = {
-            let destination_pbs_order: PBSOrder =
-                self.key_switching_key_material.destination_key.into();
-            if destination_pbs_order == self.dest_server_key.pbs_order {
-                CastCiphertext::CorrectKey(keyswitched)
-            } else {
-                // We are arriving under the wrong key for the dest_server_key
-                match self.key_switching_key_material.destination_key {
-                    // Big to Small == keyswitch
-                    EncryptionKeyChoice::Big => {
-                        let wrong_key_ct = keyswitched;
-                        let mut correct_key_ct = self.dest_server_key.create_trivial(0);
-                        correct_key_ct.degree = wrong_key_ct.degree;
-                        correct_key_ct.set_noise_level(wrong_key_ct.noise_level());
-
-                        keyswitch_lwe_ciphertext(
-                            &self.dest_server_key.key_switching_key,
-                            &wrong_key_ct.ct,
-                            &mut correct_key_ct.ct,
-                        );
-
-                        CastCiphertext::CorrectKey(correct_key_ct)
-                    }
-                    // Small to Big == PBS, we handle this in the last part of the function to apply
-                    // the refresh and the user functions in similar ways and keep the code easier
-                    // to maintain
-                    EncryptionKeyChoice::Small => CastCiphertext::WrongKeyRequiresPBS(keyswitched),
+        let destination_pbs_order: PBSOrder =
+            self.key_switching_key_material.destination_key.into();
+        if destination_pbs_order == self.dest_server_key.pbs_order {
+            CastCiphertext::CorrectKey(keyswitched)
+        } else {
+            // We are arriving under the wrong key for the dest_server_key
+            match self.key_switching_key_material.destination_key {
+                // Big to Small == keyswitch
+                EncryptionKeyChoice::Big => {
+                    let wrong_key_ct = keyswitched;
+                    let mut correct_key_ct = self.dest_server_key.create_trivial(0);
+                    correct_key_ct.degree = wrong_key_ct.degree;
+                    correct_key_ct.set_noise_level(wrong_key_ct.noise_level());
+
+                    keyswitch_lwe_ciphertext(
+                        &self.dest_server_key.key_switching_key,
+                        &wrong_key_ct.ct,
+                        &mut correct_key_ct.ct,
+                    );
+
+                    CastCiphertext::CorrectKey(correct_key_ct)
                 }
+                // Small to Big == PBS, we handle this in the last part of the function to apply
+                // the refresh and the user functions in similar ways and keep the code easier
+                // to maintain
+                EncryptionKeyChoice::Small => CastCiphertext::WrongKeyRequiresPBS(keyswitched),
             }
+        }
+    }
+
+    /// Refresh or apply the post-processing lookup tables to the output of
+    /// [`Self::keyswitch_to_destination`].
+    fn cast_with_lookup_tables_impl(
+        &self,
+        res: CastCiphertext,
+        post_processing_luts: &[LookupTableOwned],
+        using_user_provided_functions: bool,
+    ) -> Vec<Ciphertext> {
+        let cast_rshift = self.key_switching_key_material.cast_rshift;
+
+        let degree_after_keyswitch = match &res {
+            CastCiphertext::CorrectKey(ct) | CastCiphertext::WrongKeyRequiresPBS(ct) => ct.degree,
         };
 
-        let output_ciphertext_count = functions.map_or_else(|| 1, |x| x.len());
+        let output_ciphertext_count = post_processing_luts.len();
         let mut output_cts = vec![self.dest_server_key.create_trivial(0); output_ciphertext_count];
-        let identity_fn_array: &[&(dyn Fn(u64) -> u64 + Sync)] = &[&|x: u64| x];
-        let functions_to_use = functions.map_or_else(|| identity_fn_array, |fns| fns);
-        let using_user_provided_functions = functions.is_some();
         let using_identity_lut = !using_user_provided_functions;
 
         match cast_rshift.cmp(&0) {
@@ -608,11 +1676,10 @@ impl<'keys> KeySwitchingKeyView<'keys> {
                     CastCiphertext::CorrectKey(ciphertext) => {
                         output_cts
                             .par_iter_mut()
-                            .zip(functions_to_use.par_iter())
-                            .for_each(|(correct_key_ct, function)| {
-                                let acc = self.dest_server_key.generate_lookup_table(function);
+                            .zip(post_processing_luts.par_iter())
+                            .for_each(|(correct_key_ct, acc)| {
                                 *correct_key_ct =
-                                    self.dest_server_key.apply_lookup_table(&ciphertext, &acc);
+                                    self.dest_server_key.apply_lookup_table(&ciphertext, acc);
                                 // If we apply an Identity LUT we know a tighter bound than the
                                 // worst case LUT value
                                 if using_identity_lut {
@@ -623,11 +1690,10 @@ impl<'keys> KeySwitchingKeyView<'keys> {
                     CastCiphertext::WrongKeyRequiresPBS(wrong_key_ct) => {
                         output_cts
                             .par_iter_mut()
-                            .zip(functions_to_use.par_iter())
-                            .for_each(|(correct_key_ct, function)| {
+                            .zip(post_processing_luts.par_iter())
+                            .for_each(|(correct_key_ct, acc)| {
                                 ShortintEngine::with_thread_local_mut(|engine| {
                                     let (_, buffers) = engine.get_buffers(self.dest_server_key);
-                                    let acc = self.dest_server_key.generate_lookup_table(function);
                                     apply_programmable_bootstrap(
                                         &self.dest_server_key.bootstrapping_key,
                                         &wrong_key_ct.ct,
@@ -650,35 +1716,28 @@ impl<'keys> KeySwitchingKeyView<'keys> {
                     }
                 }
             }
-            // Cast to bigger bit length: keyswitch, then right shift, combine this with user
-            // function for better efficiency
+            // Cast to bigger bit length: keyswitch, then right shift to undo the shift coming from
+            // the smaller destination scaling factor, combine this with user function for better
+            // efficiency, the right shift is part of the provided LUTs
             Ordering::Greater => {
                 match res {
                     CastCiphertext::CorrectKey(ciphertext) => {
                         output_cts
                             .par_iter_mut()
-                            .zip(functions_to_use.par_iter())
-                            .for_each(|(correct_key_ct, function)| {
-                                let acc = self
-                                    .dest_server_key
-                                    .generate_lookup_table(|n| function(n >> cast_rshift));
+                            .zip(post_processing_luts.par_iter())
+                            .for_each(|(correct_key_ct, acc)| {
                                 *correct_key_ct =
-                                    self.dest_server_key.apply_lookup_table(&ciphertext, &acc);
+                                    self.dest_server_key.apply_lookup_table(&ciphertext, acc);
                                 // degree and noise are updated by the apply lookup table
                             });
                     }
                     CastCiphertext::WrongKeyRequiresPBS(wrong_key_ct) => {
                         output_cts
                             .par_iter_mut()
-                            .zip(functions_to_use.par_iter())
-                            .for_each(|(correct_key_ct, function)| {
+                            .zip(post_processing_luts.par_iter())
+                            .for_each(|(correct_key_ct, acc)| {
                                 ShortintEngine::with_thread_local_mut(|engine| {
                                     let (_, buffers) = engine.get_buffers(self.dest_server_key);
-                                    let acc = self.dest_server_key.generate_lookup_table(|n| {
-                                        // Call the function on the shifted arrival
-                                        // value
-                                        function(n >> cast_rshift)
-                                    });
                                     apply_programmable_bootstrap(
                                         &self.dest_server_key.bootstrapping_key,
                                         &wrong_key_ct.ct,
@@ -701,11 +1760,10 @@ impl<'keys> KeySwitchingKeyView<'keys> {
                     CastCiphertext::CorrectKey(ciphertext) => {
                         output_cts
                             .par_iter_mut()
-                            .zip(functions_to_use.par_iter())
-                            .for_each(|(correct_key_ct, function)| {
-                                let acc = self.dest_server_key.generate_lookup_table(function);
+                            .zip(post_processing_luts.par_iter())
+                            .for_each(|(correct_key_ct, acc)| {
                                 *correct_key_ct =
-                                    self.dest_server_key.apply_lookup_table(&ciphertext, &acc);
+                                    self.dest_server_key.apply_lookup_table(&ciphertext, acc);
 
                                 if using_user_provided_functions {
                                     correct_key_ct.degree = acc.degree;
@@ -730,11 +1788,10 @@ impl<'keys> KeySwitchingKeyView<'keys> {
                     CastCiphertext::WrongKeyRequiresPBS(wrong_key_ct) => {
                         output_cts
                             .par_iter_mut()
-                            .zip(functions_to_use.par_iter())
-                            .for_each(|(correct_key_ct, function)| {
+                            .zip(post_processing_luts.par_iter())
+                            .for_each(|(correct_key_ct, acc)| {
                                 ShortintEngine::with_thread_local_mut(|engine| {
                                     let (_, buffers) = engine.get_buffers(self.dest_server_key);
-                                    let acc = self.dest_server_key.generate_lookup_table(function);
                                     apply_programmable_bootstrap(
                                         &self.dest_server_key.bootstrapping_key,
                                         &wrong_key_ct.ct,
@@ -762,6 +1819,267 @@ impl<'keys> KeySwitchingKeyView<'keys> {
     }
 }
 
+enum CastCiphertext {
+    CorrectKey(Ciphertext),
+    WrongKeyRequiresPBS(Ciphertext),
+}
+
+fn modulus_as_f64(ciphertext_modulus: crate::shortint::CiphertextModulus) -> f64 {
+    if ciphertext_modulus.is_native_modulus() {
+        2.0f64.powi(u64::BITS as i32)
+    } else {
+        ciphertext_modulus.get_custom_modulus() as f64
+    }
+}
+
+fn estimate_keyswitch_variance(
+    key_switching_key: &LweKeyswitchKeyOwned<u64>,
+    ciphertext_modulus: crate::shortint::CiphertextModulus,
+) -> Variance {
+    keyswitch_additive_variance_132_bits_security_gaussian(
+        key_switching_key.input_key_lwe_dimension(),
+        key_switching_key.output_key_lwe_dimension(),
+        key_switching_key.decomposition_base_log(),
+        key_switching_key.decomposition_level_count(),
+        modulus_as_f64(ciphertext_modulus),
+    )
+}
+
+fn estimate_pbs_variance(server_key: &ServerKey) -> Variance {
+    let bootstrapping_key = &server_key.bootstrapping_key;
+
+    pbs_variance_132_bits_security_gaussian(
+        bootstrapping_key.input_lwe_dimension(),
+        bootstrapping_key.glwe_size().to_glwe_dimension(),
+        bootstrapping_key.polynomial_size(),
+        bootstrapping_key.decomposition_base_log(),
+        bootstrapping_key.decomposition_level_count(),
+        modulus_as_f64(server_key.ciphertext_modulus),
+    )
+}
+
+/// Variance of the output of [`ServerKey::apply_lookup_table`], which ends with a keyswitch for
+/// the [`PBSOrder::BootstrapKeyswitch`] order.
+fn estimate_lookup_table_variance(server_key: &ServerKey) -> Variance {
+    let pbs_variance = estimate_pbs_variance(server_key);
+
+    match server_key.pbs_order {
+        PBSOrder::KeyswitchBootstrap => pbs_variance,
+        PBSOrder::BootstrapKeyswitch => Variance(
+            pbs_variance.0
+                + estimate_keyswitch_variance(
+                    &server_key.key_switching_key,
+                    server_key.ciphertext_modulus,
+                )
+                .0,
+        ),
+    }
+}
+
+/// Error returned by [`KeySwitchingKey::compose`] when the destination of the first
+/// [`KeySwitchingKey`] is not the source of the second one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComposeError {
+    IntermediateLweDimensionMismatch {
+        first_output_lwe_dimension: LweDimension,
+        second_input_lwe_dimension: LweDimension,
+    },
+    IntermediateServerKeyMismatch,
+    IntermediateModulusMismatch {
+        expected_cast_rshift: i8,
+        second_cast_rshift: i8,
+    },
+    UnsupportedCast(CastError),
+}
+
+impl Display for ComposeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IntermediateLweDimensionMismatch {
+                first_output_lwe_dimension,
+                second_input_lwe_dimension,
+            } => {
+                write!(
+                    f,
+                    "Mismatch between the first KeySwitchingKey destination ciphertext \
+                    LweDimension ({first_output_lwe_dimension:?}) and the second KeySwitchingKey \
+                    input LweDimension ({second_input_lwe_dimension:?})"
+                )
+            }
+            Self::IntermediateServerKeyMismatch => {
+                write!(
+                    f,
+                    "The source ServerKey of the second KeySwitchingKey is not \
+                    the destination ServerKey of the first KeySwitchingKey"
+                )
+            }
+            Self::IntermediateModulusMismatch {
+                expected_cast_rshift,
+                second_cast_rshift,
+            } => {
+                write!(
+                    f,
+                    "The second KeySwitchingKey casts from a different modulus than the \
+                    destination of the first KeySwitchingKey, cast_rshift is \
+                    {second_cast_rshift} instead of {expected_cast_rshift}"
+                )
+            }
+            Self::UnsupportedCast(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for ComposeError {}
+
+impl From<CastError> for ComposeError {
+    fn from(value: CastError) -> Self {
+        Self::UnsupportedCast(value)
+    }
+}
+
+/// Two [`KeySwitchingKey`] applied one after the other, the destination of the first one being
+/// the source of the second one.
+///
+/// The two keyswitch keys cannot be merged into a single one, so casting with a
+/// [`ComposedKeySwitchingKey`] costs two casts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ComposedKeySwitchingKey<'keys> {
+    first: &'keys KeySwitchingKey,
+    second: &'keys KeySwitchingKey,
+}
+
+impl<'keys> ComposedKeySwitchingKey<'keys> {
+    /// Compose two [`KeySwitchingKey`], see [`KeySwitchingKey::compose`].
+    pub fn new(
+        first: &'keys KeySwitchingKey,
+        second: &'keys KeySwitchingKey,
+    ) -> Result<Self, ComposeError> {
+        let intermediate_server_key = &first.dest_server_key;
+
+        let first_output_lwe_dimension = intermediate_server_key.ciphertext_lwe_dimension();
+        let second_input_lwe_dimension = second
+            .key_switching_key_material
+            .key_switching_key
+            .input_key_lwe_dimension();
+        if first_output_lwe_dimension != second_input_lwe_dimension {
+            return Err(ComposeError::IntermediateLweDimensionMismatch {
+                first_output_lwe_dimension,
+                second_input_lwe_dimension,
+            });
+        }
+
+        if let Some(second_src_server_key) = second.src_server_key.as_ref() {
+            if second_src_server_key != intermediate_server_key {
+                return Err(ComposeError::IntermediateServerKeyMismatch);
+            }
+        }
+
+        let expected_cast_rshift = compute_cast_rshift(
+            intermediate_server_key.message_modulus,
+            intermediate_server_key.carry_modulus,
+            second.dest_server_key.message_modulus,
+            second.dest_server_key.carry_modulus,
+        )?;
+        let second_cast_rshift = second.key_switching_key_material.cast_rshift;
+        if expected_cast_rshift != second_cast_rshift {
+            return Err(ComposeError::IntermediateModulusMismatch {
+                expected_cast_rshift,
+                second_cast_rshift,
+            });
+        }
+
+        Ok(Self { first, second })
+    }
+
+    /// Deconstruct a [`ComposedKeySwitchingKey`] into the two [`KeySwitchingKey`] it applies.
+    pub fn into_raw_parts(self) -> (&'keys KeySwitchingKey, &'keys KeySwitchingKey) {
+        let Self { first, second } = self;
+
+        (first, second)
+    }
+
+    /// Cast a ciphertext from the source of the first [`KeySwitchingKey`] to the destination of
+    /// the second one, this is the same as casting with both keys in sequence.
+    pub fn cast(&self, input_ct: &Ciphertext) -> Ciphertext {
+        self.second.cast(&self.first.cast(input_ct))
+    }
+}
+
+/// Kind of operations run by [`KeySwitchingKey::cast`] on a non trivial input.
+///
+/// [`KeySwitchingKey::cast`] always refreshes its output with a PBS on the destination key, the
+/// keyswitch only path is [`KeySwitchingKey::cast_without_refresh`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CastCostClass {
+    /// Casting to the same or a bigger message modulus: the casting keyswitch followed by a PBS on
+    /// the destination key.
+    KeyswitchThenPbs,
+    /// Casting to a smaller message modulus: a PBS on the source key, the casting keyswitch and a
+    /// PBS on the destination key.
+    PbsThenKeyswitchThenPbs,
+}
+
+/// How the low bits of the keyswitched value are dropped when casting to a bigger message
+/// modulus.
+///
+/// After the casting keyswitch the value is scaled by `2^cast_rshift` under the destination key,
+/// and the low `cast_rshift` bits only hold the noise of the input ciphertext. Casting to the same
+/// or a smaller message modulus does not drop any bit and is not affected by the rounding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CastRounding {
+    /// Drop the low bits: `n >> cast_rshift`, an input noise below minus half the destination
+    /// scaling factor lowers the result by one.
+    #[default]
+    Truncate,
+    /// Round to the nearest value: `(n + 2^(cast_rshift - 1)) >> cast_rshift`, clamped to the
+    /// biggest value of the source message and carry space.
+    Nearest,
+}
+
+/// How the output of the casting keyswitch is brought under the key expected by the destination
+/// [`ServerKey`] before the PBS on the destination key.
+///
+/// All four combinations of the casting key `destination_key` and the destination [`ServerKey`]
+/// [`PBSOrder`] are valid:
+///
+/// | `destination_key` | destination [`PBSOrder`]               | adjustment                       |
+/// |-------------------|----------------------------------------|----------------------------------|
+/// | `Big`             | [`PBSOrder::KeyswitchBootstrap`]       | [`Self::None`], keyswitch + PBS  |
+/// | `Small`           | [`PBSOrder::BootstrapKeyswitch`]       | [`Self::None`], PBS + keyswitch  |
+/// | `Big`             | [`PBSOrder::BootstrapKeyswitch`]       | [`Self::ExtraKeyswitch`]         |
+/// | `Small`           | [`PBSOrder::KeyswitchBootstrap`]       | [`Self::PbsWithoutKeyswitch`]    |
+///
+/// The last combination is the cheapest one as the keyswitch of the destination [`ServerKey`] is
+/// skipped, it is the one used by e.g. `PARAM_KEYSWITCH_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DestinationKeyAdjustment {
+    /// The casting keyswitch already outputs ciphertexts under the expected key, the destination
+    /// PBS is done as for any other ciphertext of the destination [`ServerKey`].
+    None,
+    /// The casting keyswitch outputs ciphertexts under the big key while the destination
+    /// [`ServerKey`] expects the small key, an extra keyswitch with the destination
+    /// [`ServerKey`] is required.
+    ExtraKeyswitch,
+    /// The casting keyswitch outputs ciphertexts under the small key while the destination
+    /// [`ServerKey`] expects the big key, the destination PBS is applied directly without the
+    /// keyswitch of the destination [`ServerKey`].
+    PbsWithoutKeyswitch,
+}
+
+/// Lookup tables used when casting with a [`KeySwitchingKey`].
+///
+/// They only depend on the keys and can be generated once using
+/// [`KeySwitchingKey::generate_cast_lookup_tables`] to be reused with
+/// [`KeySwitchingKey::cast_with_lookup_tables`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CastLookupTables {
+    // Applied with the source server key before the keyswitch when casting to a smaller modulus
+    pub(crate) pre_processing: Option<LookupTableOwned>,
+    // Applied with the destination server key after the keyswitch
+    pub(crate) post_processing: Arc<LookupTableOwned>,
+    pub(crate) cast_rshift: i8,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Versionize)]
 #[versionize(CompressedKeySwitchingKeyMaterialVersions)]
 pub struct CompressedKeySwitchingKeyMaterial {
@@ -849,15 +2167,18 @@ impl<'keys> CompressedKeySwitchingKeyBuildHelper<'keys> {
             engine.new_seeded_key_switching_key(&input_secret_key, output_key_pair.0, params)
         });
 
+        let cast_rshift = compute_cast_rshift(
+            input_secret_key.message_modulus,
+            input_secret_key.carry_modulus,
+            output_key_pair.0.parameters.message_modulus(),
+            output_key_pair.0.parameters.carry_modulus(),
+        )
+        .unwrap_or_else(|err| panic!("Cannot create casting key: {err}"));
+
         let full_message_modulus_input =
             input_secret_key.carry_modulus.0 * input_secret_key.message_modulus.0;
         let full_message_modulus_output = output_key_pair.0.parameters.carry_modulus().0
             * output_key_pair.0.parameters.message_modulus().0;
-        assert!(
-            full_message_modulus_input.is_power_of_two()
-                && full_message_modulus_output.is_power_of_two(),
-            "Cannot create casting key if the full messages moduli are not a power of 2"
-        );
         if full_message_modulus_input > full_message_modulus_output {
             assert!(
                 input_key_pair.1.is_some(),
@@ -868,14 +2189,11 @@ impl<'keys> CompressedKeySwitchingKeyBuildHelper<'keys> {
             );
         }
 
-        let nb_bits_input: i8 = full_message_modulus_input.ilog2().try_into().unwrap();
-        let nb_bits_output: i8 = full_message_modulus_output.ilog2().try_into().unwrap();
-
         // Pack the keys in the casting key set:
         Self {
             key_switching_key_material: CompressedKeySwitchingKeyMaterial {
                 key_switching_key,
-                cast_rshift: nb_bits_output - nb_bits_input,
+                cast_rshift,
                 destination_key: params.destination_key,
             },
             dest_server_key: output_key_pair.1,
@@ -904,6 +2222,7 @@ impl CompressedKeySwitchingKey {
                 .src_server_key
                 .as_ref()
                 .map(CompressedServerKey::decompress),
+            cast_lookup_tables: CastLookupTablesCache::default(),
         }
     }
 
@@ -938,42 +2257,51 @@ impl CompressedKeySwitchingKey {
     /// [`LweDimension`](`crate::core_crypto::commons::parameters::LweDimension`) does not match
     /// the output [`LweDimension`](`crate::core_crypto::commons::parameters::LweDimension`) of
     /// the [`SeededLweKeyswitchKeyOwned`] in the provided [`CompressedKeySwitchingKeyMaterial`].
+    ///
+    /// Also panics if a source server key is provided and the `cast_rshift` of the provided
+    /// [`CompressedKeySwitchingKeyMaterial`] is not the one computed by [`compute_cast_rshift`]
+    /// from the source and destination moduli.
     pub fn from_raw_parts(
         key_switching_key_material: CompressedKeySwitchingKeyMaterial,
         dest_server_key: CompressedServerKey,
         src_server_key: Option<CompressedServerKey>,
     ) -> Self {
-        match src_server_key {
-            Some(ref src_server_key) => {
-                let src_lwe_dimension = src_server_key.ciphertext_lwe_dimension();
-
-                assert_eq!(
-                    src_lwe_dimension,
-                    key_switching_key_material
-                        .key_switching_key
-                        .input_key_lwe_dimension(),
-                    "Mismatch between the source CompressedServerKey ciphertext LweDimension ({:?}) \
-                    and the SeededLweKeyswitchKey input LweDimension ({:?})",
-                    src_lwe_dimension,
-                    key_switching_key_material
-                        .key_switching_key
-                        .input_key_lwe_dimension(),
-                );
+        if let Some(ref src_server_key) = src_server_key {
+            let src_lwe_dimension = src_server_key.ciphertext_lwe_dimension();
+
+            assert_eq!(
+                src_lwe_dimension,
+                key_switching_key_material
+                    .key_switching_key
+                    .input_key_lwe_dimension(),
+                "Mismatch between the source CompressedServerKey ciphertext LweDimension ({:?}) \
+                and the SeededLweKeyswitchKey input LweDimension ({:?})",
+                src_lwe_dimension,
+                key_switching_key_material
+                    .key_switching_key
+                    .input_key_lwe_dimension(),
+            );
 
-                assert_eq!(
-                    src_server_key.ciphertext_modulus, dest_server_key.ciphertext_modulus,
-                    "Mismatch between the source CompressedServerKey CiphertextModulus ({:?}) \
-                    and the destination CompressedServerKey CiphertextModulus ({:?})",
-                    src_server_key.ciphertext_modulus, dest_server_key.ciphertext_modulus,
-                );
-            }
-            None => assert!(
-                key_switching_key_material.cast_rshift >= 0,
-                "Trying to build a shortint::CompressedKeySwitchingKey with a negative cast_rshift \
-                without providing a source CompressedServerKey, this is not supported"
-            ),
+            assert_eq!(
+                src_server_key.ciphertext_modulus, dest_server_key.ciphertext_modulus,
+                "Mismatch between the source CompressedServerKey CiphertextModulus ({:?}) \
+                and the destination CompressedServerKey CiphertextModulus ({:?})",
+                src_server_key.ciphertext_modulus, dest_server_key.ciphertext_modulus,
+            );
         }
 
+        check_cast_rshift(
+            src_server_key
+                .as_ref()
+                .map(|key| (key.message_modulus, key.carry_modulus)),
+            (
+                dest_server_key.message_modulus,
+                dest_server_key.carry_modulus,
+            ),
+            key_switching_key_material.cast_rshift,
+        )
+        .unwrap_or_else(|err| panic!("{err}"));
+
         let dst_lwe_dimension = match key_switching_key_material.destination_key {
             EncryptionKeyChoice::Big => dest_server_key.bootstrapping_key.output_lwe_dimension(),
             EncryptionKeyChoice::Small => dest_server_key.bootstrapping_key.input_lwe_dimension(),
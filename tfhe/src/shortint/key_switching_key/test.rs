@@ -1,9 +1,28 @@
+use super::{
+    compute_cast_rshift, CastCostClass, CastError, CastLookupTables, CastRounding, ComposeError,
+    DestinationKeyAdjustment, KeySwitchingKeyBuildHelper, KeySwitchingKeyMaterial,
+    KeySwitchingKeyMaterialView, KeySwitchingKeyView,
+};
+use crate::core_crypto::commons::dispersion::Variance;
+use crate::core_crypto::commons::generators::DeterministicSeeder;
+use crate::core_crypto::commons::test_tools::{torus_modular_diff, variance};
+use crate::core_crypto::prelude::{decrypt_lwe_ciphertext, ActivatedRandomGenerator};
+use crate::safe_serialization::{safe_deserialize, safe_serialize};
+use crate::shortint::ciphertext::Degree;
+use crate::shortint::client_key::secret_encryption_key::SecretEncryptionKeyView;
+use crate::shortint::engine::ShortintEngine;
 use crate::shortint::keycache::{KEY_CACHE, KEY_CACHE_KSK};
+use crate::shortint::parameters::list_compression::COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
 use crate::shortint::parameters::{
-    ShortintKeySwitchingParameters, PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
-    PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
+    NoiseLevel, ShortintKeySwitchingParameters, PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+    PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
+    PARAM_MESSAGE_2_CARRY_2_PBS_KS_GAUSSIAN_2M64, PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
 };
 use crate::shortint::prelude::*;
+use concrete_csprng::seeders::Seed;
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::sync::Arc;
 
 #[test]
 fn gen_multi_keys_test_fresh_ci_run_filter() {
@@ -236,3 +255,1200 @@ fn gen_multi_keys_test_truncate_ci_run_filter() {
     let carry = ck2.decrypt(&ct_carry);
     assert_eq!(carry, 0);
 }
+
+#[test]
+fn gen_multi_keys_test_cast_with_lookup_tables_ci_run_filter() {
+    // Downcast, upcast and same size cast
+    for (src_params, dst_params) in [
+        (
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ),
+        (
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        ),
+        (
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ),
+    ] {
+        let ksk_params = ShortintKeySwitchingParameters::new(
+            dst_params.ks_base_log,
+            dst_params.ks_level,
+            dst_params.encryption_key_choice,
+        );
+
+        let keys = KEY_CACHE_KSK.get_from_param((src_params, dst_params, ksk_params));
+        let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+        let ksk = keys.key_switching_key();
+
+        let luts = ksk.generate_cast_lookup_tables();
+        assert_eq!(
+            luts.pre_processing.is_some(),
+            ksk.key_switching_key_material.cast_rshift < 0
+        );
+
+        let msg_modulus = ck1
+            .parameters
+            .message_modulus()
+            .0
+            .min(ck2.parameters.message_modulus().0) as u64;
+
+        for msg in 0..msg_modulus {
+            let cipher = ck1.encrypt(msg);
+            let output_of_cast = ksk.cast(&cipher);
+            let output_of_cast_with_luts = ksk.cast_with_lookup_tables(&cipher, &luts);
+
+            // Keyswitch and PBS are deterministic, so both paths must give the exact same result
+            assert_eq!(output_of_cast_with_luts, output_of_cast);
+            assert_eq!(ck2.decrypt(&output_of_cast_with_luts), msg);
+        }
+    }
+}
+
+#[test]
+fn gen_multi_keys_test_cast_lookup_tables_are_cached_ci_run_filter() {
+    // Downcast, the cached tables then also hold the pre-processing lookup table
+    let ksk_params = ShortintKeySwitchingParameters::new(
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.ks_base_log,
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.ks_level,
+        EncryptionKeyChoice::Big,
+    );
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ksk_params,
+    ));
+    let (ck1, sk1) = (keys.client_key_1(), keys.server_key_1());
+    let (ck2, sk2) = (keys.client_key_2(), keys.server_key_2());
+
+    // Rebuild the key to start from an empty cache, the key cache one may already have been used
+    let (material, _, _) = keys.key_switching_key().clone().into_raw_parts();
+    let ksk = KeySwitchingKey::from_raw_parts(material, sk2.clone(), Some(sk1.clone()));
+
+    assert!(ksk.cast_lookup_tables.get().is_none());
+
+    let cipher = ck1.encrypt(1);
+    let output_of_cast = ksk.cast(&cipher);
+    assert_eq!(ck2.decrypt(&output_of_cast), 1);
+
+    // The first cast generated the tables and stored them in the key
+    let cached: *const CastLookupTables = ksk.cast_lookup_tables.get().unwrap();
+    assert_eq!(ksk.cast_lookup_tables(), &ksk.generate_cast_lookup_tables());
+    assert!(ksk.cast_lookup_tables().pre_processing.is_some());
+
+    let msg_modulus = ck2.parameters.message_modulus().0 as u64;
+    for msg in 0..msg_modulus {
+        let cipher = ck1.encrypt(msg);
+        let output_of_cast = ksk.cast(&cipher);
+        assert_eq!(ck2.decrypt(&output_of_cast), msg);
+    }
+
+    // Later casts reused the same tables instead of generating new ones
+    assert!(std::ptr::eq(ksk.cast_lookup_tables.get().unwrap(), cached));
+    assert!(std::ptr::eq(ksk.cast_lookup_tables(), cached));
+
+    // A view of the key borrows them
+    let view = ksk.as_view();
+    assert!(matches!(
+        view.cast_lookup_tables(),
+        Cow::Borrowed(tables) if std::ptr::eq(tables, cached)
+    ));
+
+    // A view built from raw parts has nowhere to store them
+    let (material, dest_server_key, src_server_key) = view.into_raw_parts();
+    let raw_view = KeySwitchingKeyView::from_raw_parts(material, dest_server_key, src_server_key);
+    assert!(matches!(raw_view.cast_lookup_tables(), Cow::Owned(_)));
+
+    // The tables are neither serialized nor compared
+    let mut serialized = Vec::new();
+    safe_serialize(&ksk, &mut serialized, 1 << 30).unwrap();
+    let deserialized: KeySwitchingKey = safe_deserialize(serialized.as_slice(), 1 << 30).unwrap();
+
+    assert!(deserialized.cast_lookup_tables.get().is_none());
+    assert_eq!(deserialized, ksk);
+}
+
+#[test]
+fn gen_multi_keys_test_identity_lookup_table_built_once_per_server_key_ci_run_filter() {
+    // Same size casts to the same destination server key, with a Small destination key the cast
+    // ends with the PBS of the Small to Big tail
+    let [keys_big, keys_small] =
+        [EncryptionKeyChoice::Big, EncryptionKeyChoice::Small].map(|destination_key| {
+            KEY_CACHE_KSK.get_from_param((
+                PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+                PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+                ShortintKeySwitchingParameters::new(
+                    PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.ks_base_log,
+                    PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.ks_level,
+                    destination_key,
+                ),
+            ))
+        });
+    let (ck1, ck2) = (keys_big.client_key_1(), keys_big.client_key_2());
+    let sk2 = keys_big.server_key_2();
+
+    assert_eq!(
+        keys_small.key_switching_key().destination_key_adjustment(),
+        DestinationKeyAdjustment::PbsWithoutKeyswitch
+    );
+
+    // Rebuild the keys so that their cast lookup tables are generated on this thread
+    let [ksk_big, ksk_small] = [&keys_big, &keys_small].map(|keys| {
+        let (material, dest_server_key, src_server_key) =
+            keys.key_switching_key().clone().into_raw_parts();
+        KeySwitchingKey::from_raw_parts(material, dest_server_key, src_server_key)
+    });
+
+    let identity_lut =
+        ShortintEngine::with_thread_local_mut(|engine| engine.identity_lookup_table(sk2));
+    assert_eq!(*identity_lut, sk2.generate_lookup_table(|x| x));
+
+    // Asking again for the same server key does not build a new lookup table
+    let identity_lut_again =
+        ShortintEngine::with_thread_local_mut(|engine| engine.identity_lookup_table(sk2));
+    assert!(Arc::ptr_eq(&identity_lut, &identity_lut_again));
+
+    let msg_modulus = ck1.parameters.message_modulus().0 as u64;
+    for ksk in [&ksk_big, &ksk_small] {
+        for msg in 0..msg_modulus {
+            let cipher = ck1.encrypt(msg);
+            let output_of_cast = ksk.cast(&cipher);
+            assert_eq!(ck2.decrypt(&output_of_cast), msg);
+        }
+
+        // Both casting keys use the lookup table built for their destination server key
+        assert!(Arc::ptr_eq(
+            &ksk.cast_lookup_tables().post_processing,
+            &identity_lut
+        ));
+    }
+
+    // A server key with other parameters gets its own identity lookup table
+    let other_keys = KEY_CACHE.get_from_param(PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64);
+    let other_sk = other_keys.server_key();
+    let other_identity_lut =
+        ShortintEngine::with_thread_local_mut(|engine| engine.identity_lookup_table(other_sk));
+    assert!(!Arc::ptr_eq(&identity_lut, &other_identity_lut));
+    assert_eq!(*other_identity_lut, other_sk.generate_lookup_table(|x| x));
+}
+
+#[test]
+fn gen_multi_keys_test_safe_serialization_ci_run_filter() {
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    ));
+    let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+    let ksk = keys.key_switching_key();
+
+    let mut serialized = Vec::new();
+    safe_serialize(ksk, &mut serialized, 1 << 30).unwrap();
+    let deserialized: KeySwitchingKey = safe_deserialize(serialized.as_slice(), 1 << 30).unwrap();
+
+    assert_eq!(&deserialized, ksk);
+
+    for msg in 0..2 {
+        let cipher = ck1.encrypt(msg);
+        let output_of_cast = deserialized.cast(&cipher);
+        assert_eq!(output_of_cast, ksk.cast(&cipher));
+        assert_eq!(ck2.decrypt(&output_of_cast), msg);
+    }
+}
+
+#[test]
+fn gen_multi_keys_test_material_safe_serialization_ci_run_filter() {
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    ));
+    let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+    let (sk1, sk2) = (keys.server_key_1(), keys.server_key_2());
+    let ksk = keys.key_switching_key();
+
+    let mut serialized_material = Vec::new();
+    safe_serialize(
+        ksk.key_switching_key_material(),
+        &mut serialized_material,
+        1 << 30,
+    )
+    .unwrap();
+    let mut serialized_key = Vec::new();
+    safe_serialize(ksk, &mut serialized_key, 1 << 30).unwrap();
+
+    assert!(serialized_material.len() < serialized_key.len());
+
+    let material: KeySwitchingKeyMaterial =
+        safe_deserialize(serialized_material.as_slice(), 1 << 30).unwrap();
+
+    assert_eq!(&material, ksk.key_switching_key_material());
+
+    let borrowed = KeySwitchingKeyView::from_raw_parts(material.as_view(), sk2, Some(sk1));
+    let owned = KeySwitchingKey::from_raw_parts(material.clone(), sk2.clone(), Some(sk1.clone()));
+
+    assert_eq!(&owned, ksk);
+
+    for msg in 0..2 {
+        let cipher = ck1.encrypt(msg);
+        let expected = ksk.cast(&cipher);
+
+        let output_of_cast = borrowed.cast(&cipher);
+        assert_eq!(output_of_cast, expected);
+        assert_eq!(ck2.decrypt(&output_of_cast), msg);
+
+        let output_of_cast = owned.cast(&cipher);
+        assert_eq!(output_of_cast, expected);
+        assert_eq!(ck2.decrypt(&output_of_cast), msg);
+    }
+}
+
+#[test]
+fn gen_multi_keys_test_trivial_cast_ci_run_filter() {
+    // Downcast, upcast and same size cast
+    for (src_params, dst_params) in [
+        (
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ),
+        (
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        ),
+        (
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ),
+    ] {
+        let ksk_params = ShortintKeySwitchingParameters::new(
+            dst_params.ks_base_log,
+            dst_params.ks_level,
+            dst_params.encryption_key_choice,
+        );
+
+        let keys = KEY_CACHE_KSK.get_from_param((src_params, dst_params, ksk_params));
+        let (ck1, sk1) = (keys.client_key_1(), keys.server_key_1());
+        let (ck2, sk2) = (keys.client_key_2(), keys.server_key_2());
+        let ksk = keys.key_switching_key();
+
+        let full_modulus =
+            (ck1.parameters.message_modulus().0 * ck1.parameters.carry_modulus().0) as u64;
+
+        for msg in 0..full_modulus {
+            let trivial = sk1.unchecked_create_trivial(msg);
+            let output_of_cast = ksk.cast(&trivial);
+
+            assert!(output_of_cast.is_trivial());
+            assert_eq!(output_of_cast.noise_level(), NoiseLevel::ZERO);
+            assert_eq!(
+                output_of_cast.ct.lwe_size(),
+                sk2.create_trivial(0).ct.lwe_size()
+            );
+
+            // Must match what the encrypted path computes
+            let encrypted = ck1.unchecked_encrypt(msg);
+            let expected = ck2.decrypt_message_and_carry(&ksk.cast(&encrypted));
+            assert_eq!(
+                output_of_cast.decrypt_trivial_message_and_carry(),
+                Ok(expected)
+            );
+        }
+    }
+}
+
+#[test]
+fn gen_multi_keys_test_cast_without_refresh_ci_run_filter() {
+    // Downcast and same size cast, both can be done without a PBS on the destination key
+    for (src_params, dst_params) in [
+        (
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ),
+        (
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ),
+    ] {
+        let ksk_params = ShortintKeySwitchingParameters::new(
+            dst_params.ks_base_log,
+            dst_params.ks_level,
+            dst_params.encryption_key_choice,
+        );
+
+        let keys = KEY_CACHE_KSK.get_from_param((src_params, dst_params, ksk_params));
+        let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+        let ksk = keys.key_switching_key();
+
+        let msg_modulus = ck2.parameters.message_modulus().0 as u64;
+
+        for msg in 0..msg_modulus {
+            let cipher = ck1.encrypt(msg);
+
+            let refreshed = ksk.cast(&cipher);
+            assert_eq!(refreshed.noise_level(), NoiseLevel::NOMINAL);
+
+            let not_refreshed = ksk.cast_without_refresh(&cipher);
+            assert_eq!(not_refreshed.noise_level(), NoiseLevel::UNKNOWN);
+            assert_eq!(not_refreshed.degree, refreshed.degree);
+
+            assert_eq!(ck2.decrypt(&refreshed), msg);
+            assert_eq!(ck2.decrypt(&not_refreshed), msg);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "Casting to a bigger message modulus requires a PBS")]
+fn gen_multi_keys_test_cast_without_refresh_upcast_panics_ci_run_filter() {
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    ));
+    let ck1 = keys.client_key_1();
+    let ksk = keys.key_switching_key();
+
+    let cipher = ck1.encrypt(1);
+    let _ = ksk.cast_without_refresh(&cipher);
+}
+
+#[test]
+fn test_compute_cast_rshift() {
+    // 4 bits -> 2 bits
+    assert_eq!(
+        compute_cast_rshift(
+            MessageModulus(4),
+            CarryModulus(4),
+            MessageModulus(2),
+            CarryModulus(2),
+        ),
+        Ok(-2)
+    );
+
+    // 1 bit -> 4 bits
+    assert_eq!(
+        compute_cast_rshift(
+            MessageModulus(2),
+            CarryModulus(1),
+            MessageModulus(4),
+            CarryModulus(4),
+        ),
+        Ok(3)
+    );
+
+    assert_eq!(
+        compute_cast_rshift(
+            MessageModulus(3),
+            CarryModulus(1),
+            MessageModulus(4),
+            CarryModulus(4),
+        ),
+        Err(CastError::NonPowerOfTwoInputModulus {
+            full_message_modulus: 3
+        })
+    );
+
+    assert_eq!(
+        compute_cast_rshift(
+            MessageModulus(4),
+            CarryModulus(4),
+            MessageModulus(4),
+            CarryModulus(3),
+        ),
+        Err(CastError::NonPowerOfTwoOutputModulus {
+            full_message_modulus: 12
+        })
+    );
+}
+
+#[test]
+fn gen_multi_keys_test_from_existing_ksk_ci_run_filter() {
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    ));
+    let (ck1, sk1) = (keys.client_key_1(), keys.server_key_1());
+    let (ck2, sk2) = (keys.client_key_2(), keys.server_key_2());
+    let ksk = keys.key_switching_key();
+
+    let lwe_ksk = ksk.key_switching_key_material.key_switching_key.clone();
+
+    let rebuilt_ksk = KeySwitchingKey::from_existing_ksk(
+        lwe_ksk.clone(),
+        sk2.clone(),
+        sk1.clone(),
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    )
+    .unwrap();
+
+    assert_eq!(&rebuilt_ksk, ksk);
+
+    for msg in 0..ck1.parameters.message_modulus().0 as u64 {
+        let ct = ck1.encrypt(msg);
+        let cast = ksk.cast(&ct);
+        let rebuilt_cast = rebuilt_ksk.cast(&ct);
+
+        assert_eq!(ck2.decrypt(&cast), msg);
+        assert_eq!(ck2.decrypt(&rebuilt_cast), msg);
+    }
+
+    // Server keys swapped, the keyswitch key dimensions don't match anymore
+    assert!(KeySwitchingKey::from_existing_ksk(
+        lwe_ksk.clone(),
+        sk1.clone(),
+        sk2.clone(),
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    )
+    .is_err());
+
+    // Parameters not matching the keyswitch key decomposition
+    let wrong_params = ShortintKeySwitchingParameters::new(
+        DecompositionBaseLog(PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS.ks_base_log.0 + 1),
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS.ks_level,
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS.destination_key,
+    );
+    assert!(
+        KeySwitchingKey::from_existing_ksk(lwe_ksk, sk2.clone(), sk1.clone(), wrong_params)
+            .is_err()
+    );
+}
+
+#[test]
+fn gen_multi_keys_test_cast_cost_class_ci_run_filter() {
+    for (src_params, dst_params, destination_key, expected_class, expected_adjustment) in [
+        (
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            EncryptionKeyChoice::Big,
+            CastCostClass::PbsThenKeyswitchThenPbs,
+            DestinationKeyAdjustment::None,
+        ),
+        (
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            EncryptionKeyChoice::Big,
+            CastCostClass::KeyswitchThenPbs,
+            DestinationKeyAdjustment::None,
+        ),
+        (
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            EncryptionKeyChoice::Small,
+            CastCostClass::KeyswitchThenPbs,
+            DestinationKeyAdjustment::PbsWithoutKeyswitch,
+        ),
+    ] {
+        let ksk_params = ShortintKeySwitchingParameters::new(
+            dst_params.ks_base_log,
+            dst_params.ks_level,
+            destination_key,
+        );
+
+        let keys = KEY_CACHE_KSK.get_from_param((src_params, dst_params, ksk_params));
+        let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+        let ksk = keys.key_switching_key();
+
+        assert_eq!(ksk.cast_cost_class(), expected_class);
+        assert_eq!(ksk.destination_key_adjustment(), expected_adjustment);
+
+        let msg_modulus = ck1
+            .parameters
+            .message_modulus()
+            .0
+            .min(ck2.parameters.message_modulus().0) as u64;
+
+        for msg in 0..msg_modulus {
+            let cipher = ck1.encrypt(msg);
+            let output_of_cast = ksk.cast(&cipher);
+            assert_eq!(ck2.decrypt(&output_of_cast), msg);
+        }
+    }
+}
+
+#[test]
+fn gen_multi_keys_test_cast_all_param_pairs_ci_run_filter() {
+    let params = [
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
+    ];
+
+    for src_params in params {
+        for dst_params in params {
+            // Big is the key expected by the KS_PBS destination server key, Small goes through
+            // the wrong key PBS path
+            for destination_key in [EncryptionKeyChoice::Big, EncryptionKeyChoice::Small] {
+                let ksk_params = ShortintKeySwitchingParameters::new(
+                    dst_params.ks_base_log,
+                    dst_params.ks_level,
+                    destination_key,
+                );
+
+                let keys = KEY_CACHE_KSK.get_from_param((src_params, dst_params, ksk_params));
+                let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+                let ksk = keys.key_switching_key();
+
+                let full_in = (src_params.message_modulus.0 * src_params.carry_modulus.0) as u64;
+                let full_out = (dst_params.message_modulus.0 * dst_params.carry_modulus.0) as u64;
+
+                // Values in the carries are kept as long as they fit in the output modulus
+                for msg in 0..full_in {
+                    let cipher = ck1.unchecked_encrypt(msg);
+                    let output_of_cast = ksk.cast(&cipher);
+
+                    assert_eq!(
+                        ck2.decrypt_message_and_carry(&output_of_cast),
+                        msg % full_out,
+                        "Invalid cast of {msg} from {:?} to {:?} with {destination_key:?} \
+                        destination key",
+                        src_params.message_modulus,
+                        dst_params.message_modulus,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn gen_multi_keys_test_downcast_keeps_padding_bit_clean_ci_run_filter() {
+    let keys2 = KEY_CACHE.get_from_param(PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64);
+    let ck2 = keys2.client_key();
+
+    let ksk_params = ShortintKeySwitchingParameters::new(
+        ck2.parameters.ks_base_log(),
+        ck2.parameters.ks_level(),
+        ck2.parameters.encryption_key_choice(),
+    );
+
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ksk_params,
+    ));
+    let ck1 = keys.client_key_1();
+    let (ck2, sk2) = (keys.client_key_2(), keys.server_key_2());
+    let ksk = keys.key_switching_key();
+
+    assert_eq!(ksk.key_switching_key_material.cast_rshift, -2);
+
+    let full_out = (ck2.parameters.message_modulus().0 * ck2.parameters.carry_modulus().0) as u64;
+
+    // A dirty padding bit would make the PBS return the negacyclic image of the LUT
+    let f = |x: u64| (x + 1) % full_out;
+    let lut = sk2.generate_lookup_table(f);
+
+    // Every 4 bits value, including the ones using the carries
+    for msg in 0..16 {
+        let cipher = ck1.unchecked_encrypt(msg);
+
+        for output_of_cast in [ksk.cast(&cipher), ksk.cast_without_refresh(&cipher)] {
+            assert_eq!(
+                ck2.decrypt_message_and_carry(&output_of_cast),
+                msg % full_out
+            );
+
+            let res = sk2.apply_lookup_table(&output_of_cast, &lut);
+            assert_eq!(ck2.decrypt_message_and_carry(&res), f(msg % full_out));
+        }
+    }
+}
+
+#[test]
+fn gen_multi_keys_test_composed_cast_ci_run_filter() {
+    let ksk_params_2 = ShortintKeySwitchingParameters::new(
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64.ks_base_log,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64.ks_level,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64.encryption_key_choice,
+    );
+    let ksk_params_1 = ShortintKeySwitchingParameters::new(
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.ks_base_log,
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.ks_level,
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.encryption_key_choice,
+    );
+
+    // 1_1 -> 2_2 -> 1_1
+    let keys_1_to_2 = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        ksk_params_2,
+    ));
+    let keys_2_to_1 = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ksk_params_1,
+    ));
+    let ck1 = keys_1_to_2.client_key_1();
+    let ck3 = keys_2_to_1.client_key_2();
+    let ksk_1_to_2 = keys_1_to_2.key_switching_key();
+    let ksk_2_to_1 = keys_2_to_1.key_switching_key();
+
+    let composed = ksk_1_to_2.compose(ksk_2_to_1).unwrap();
+
+    for msg in 0..ck1.parameters.message_modulus().0 as u64 {
+        let cipher = ck1.encrypt(msg);
+
+        let two_steps = ksk_2_to_1.cast(&ksk_1_to_2.cast(&cipher));
+        let composed_cast = composed.cast(&cipher);
+
+        // Keyswitch and PBS are deterministic
+        assert_eq!(composed_cast, two_steps);
+        assert_eq!(ck3.decrypt(&composed_cast), msg);
+    }
+
+    // The destination of the first key is not the source of the second one, both have the same
+    // LweDimension
+    assert_eq!(
+        ksk_1_to_2.compose(ksk_1_to_2),
+        Err(ComposeError::IntermediateServerKeyMismatch)
+    );
+}
+
+#[test]
+fn gen_multi_keys_test_par_new_ci_run_filter() {
+    let keys1 = KEY_CACHE.get_from_param(PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64);
+    let keys2 = KEY_CACHE.get_from_param(PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64);
+    let (ck1, sk1) = (keys1.client_key(), keys1.server_key());
+    let (ck2, sk2) = (keys2.client_key(), keys2.server_key());
+
+    let ksk = KeySwitchingKey::par_new(
+        (ck1, Some(sk1)),
+        (ck2, sk2),
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    );
+
+    assert_eq!(ksk.key_switching_key_material.cast_rshift, 2);
+
+    let full_modulus =
+        (ck1.parameters.message_modulus().0 * ck1.parameters.carry_modulus().0) as u64;
+    for msg in 0..full_modulus {
+        let cipher = ck1.unchecked_encrypt(msg);
+        let output_of_cast = ksk.cast(&cipher);
+        assert_eq!(ck2.decrypt_message_and_carry(&output_of_cast), msg);
+    }
+}
+
+#[test]
+fn gen_multi_keys_test_par_new_matches_new_with_same_seed_ci_run_filter() {
+    let keys1 = KEY_CACHE.get_from_param(PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64);
+    let keys2 = KEY_CACHE.get_from_param(PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64);
+    let (ck1, sk1) = (keys1.client_key(), keys1.server_key());
+    let (ck2, sk2) = (keys2.client_key(), keys2.server_key());
+    let params = PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS;
+
+    let input_secret_key: SecretEncryptionKeyView<'_> = ck1.into();
+
+    // Both engines start from the same seed, so the sequential and parallel generations consume
+    // the same random bytes
+    let [ksk, par_ksk]: [KeySwitchingKey; 2] = [false, true].map(|parallel| {
+        let mut engine = ShortintEngine::new_from_seeder(&mut DeterministicSeeder::<
+            ActivatedRandomGenerator,
+        >::new(Seed(0)));
+
+        let key_switching_key = if parallel {
+            engine.par_new_key_switching_key(&input_secret_key, ck2, params)
+        } else {
+            engine.new_key_switching_key(&input_secret_key, ck2, params)
+        };
+
+        KeySwitchingKeyBuildHelper::from_key_switching_key(
+            key_switching_key,
+            &input_secret_key,
+            Some(sk1),
+            (ck2, sk2),
+            params,
+        )
+        .into()
+    });
+
+    assert_eq!(
+        ksk.key_switching_key_material,
+        par_ksk.key_switching_key_material
+    );
+
+    let full_modulus =
+        (ck1.parameters.message_modulus().0 * ck1.parameters.carry_modulus().0) as u64;
+    for msg in 0..full_modulus {
+        let cipher = ck1.unchecked_encrypt(msg);
+        assert_eq!(ck2.decrypt_message_and_carry(&ksk.cast(&cipher)), msg);
+        assert_eq!(ck2.decrypt_message_and_carry(&par_ksk.cast(&cipher)), msg);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Mismatch between the provided cast_rshift")]
+fn gen_multi_keys_test_from_raw_parts_inconsistent_cast_rshift_ci_run_filter() {
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    ));
+
+    let (material, dest_server_key, src_server_key) =
+        keys.key_switching_key().clone().into_raw_parts();
+    assert_eq!(material.cast_rshift, 2);
+
+    let (key_switching_key, _, destination_key) = material.into_raw_parts();
+    // The shift of a downcast stored in an upcasting key
+    let inconsistent_material =
+        KeySwitchingKeyMaterial::from_raw_parts(key_switching_key, -2, destination_key);
+
+    let _ = KeySwitchingKey::from_raw_parts(inconsistent_material, dest_server_key, src_server_key);
+}
+
+#[test]
+fn gen_multi_keys_test_upcast_preserves_value_ci_run_filter() {
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    ));
+    let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+    let ksk = keys.key_switching_key();
+
+    assert_eq!(ksk.key_switching_key_material.cast_rshift, 2);
+
+    // 3 fills the 2 bits of message and carry of the source, it must not be shifted out
+    let cipher = ck1.unchecked_encrypt(3);
+    let output_of_cast = ksk.cast(&cipher);
+
+    assert_eq!(ck2.decrypt_message_and_carry(&output_of_cast), 3);
+    assert_eq!(output_of_cast.degree.get(), 3);
+    assert_eq!(output_of_cast.noise_level(), NoiseLevel::NOMINAL);
+}
+
+#[test]
+fn gen_multi_keys_test_cast_rounding_ci_run_filter() {
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS,
+    ));
+    let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+    let ksk = keys.key_switching_key();
+
+    let cast_rshift = ksk.key_switching_key_material.cast_rshift;
+    assert_eq!(cast_rshift, 2);
+
+    let full_message_modulus_input =
+        (ck1.parameters.message_modulus().0 * ck1.parameters.carry_modulus().0) as u64;
+    let full_message_modulus_output =
+        (ck2.parameters.message_modulus().0 * ck2.parameters.carry_modulus().0) as u64;
+    // One step of the destination encoding, i.e. the weight of the lowest dropped bit
+    let output_delta = (1u64 << 63) / full_message_modulus_output;
+
+    let truncate_luts = ksk.generate_cast_lookup_tables_with_rounding(CastRounding::Truncate);
+    let nearest_luts = ksk.generate_cast_lookup_tables_with_rounding(CastRounding::Nearest);
+
+    assert_eq!(truncate_luts, ksk.generate_cast_lookup_tables());
+
+    for msg in 0..full_message_modulus_input {
+        // Fresh ciphertexts have a small noise, both roundings give the input value
+        let cipher = ck1.unchecked_encrypt(msg);
+        for luts in [&truncate_luts, &nearest_luts] {
+            let output_of_cast = ksk.cast_with_lookup_tables(&cipher, luts);
+            assert_eq!(ck2.decrypt_message_and_carry(&output_of_cast), msg);
+        }
+        let output_of_cast = ksk.cast_with_rounding(&cipher, CastRounding::Nearest);
+        assert_eq!(ck2.decrypt_message_and_carry(&output_of_cast), msg);
+
+        // A message of 0 with a negative noise would land in the padding part of the
+        // destination encoding
+        if msg == 0 {
+            continue;
+        }
+
+        // Move the input one destination step down, the value after the keyswitch is then
+        // (msg << cast_rshift) - 1: truncation is biased towards the lower value while rounding to
+        // the nearest value still gives the input value
+        let mut noisy_cipher = cipher.clone();
+        let body = noisy_cipher.ct.get_mut_body().data;
+        *body = body.wrapping_sub(output_delta);
+
+        let truncated = ksk.cast_with_lookup_tables(&noisy_cipher, &truncate_luts);
+        assert_eq!(ck2.decrypt_message_and_carry(&truncated), msg - 1);
+
+        let rounded = ksk.cast_with_lookup_tables(&noisy_cipher, &nearest_luts);
+        assert_eq!(ck2.decrypt_message_and_carry(&rounded), msg);
+    }
+
+    // Same bit size casts drop no bit, the rounding does not change the lookup tables
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ShortintKeySwitchingParameters::new(
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.ks_base_log,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.ks_level,
+            EncryptionKeyChoice::Big,
+        ),
+    ));
+    let ksk = keys.key_switching_key();
+    assert_eq!(
+        ksk.generate_cast_lookup_tables_with_rounding(CastRounding::Nearest),
+        ksk.generate_cast_lookup_tables_with_rounding(CastRounding::Truncate),
+    );
+}
+
+#[test]
+fn gen_multi_keys_test_cast_destination_key_adjustment_ci_run_filter() {
+    let src_params_list = [
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
+    ];
+
+    for (dst_params, destination_key, expected_adjustment) in [
+        // Arriving under the big key while the PBS_KS destination expects the small one
+        (
+            PARAM_MESSAGE_2_CARRY_2_PBS_KS_GAUSSIAN_2M64,
+            EncryptionKeyChoice::Big,
+            DestinationKeyAdjustment::ExtraKeyswitch,
+        ),
+        // Arriving under the small key while the KS_PBS destination expects the big one
+        (
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            EncryptionKeyChoice::Small,
+            DestinationKeyAdjustment::PbsWithoutKeyswitch,
+        ),
+    ] {
+        for src_params in src_params_list {
+            let ksk_params = ShortintKeySwitchingParameters::new(
+                dst_params.ks_base_log,
+                dst_params.ks_level,
+                destination_key,
+            );
+
+            let keys = KEY_CACHE_KSK.get_from_param((src_params, dst_params, ksk_params));
+            let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+            let ksk = keys.key_switching_key();
+
+            assert_eq!(ksk.destination_key_adjustment(), expected_adjustment);
+
+            let cast_rshift = ksk.key_switching_key_material.cast_rshift;
+            let full_in = (src_params.message_modulus.0 * src_params.carry_modulus.0) as u64;
+            let full_out = (dst_params.message_modulus.0 * dst_params.carry_modulus.0) as u64;
+
+            for msg in 0..full_in {
+                let cipher = ck1.unchecked_encrypt(msg);
+
+                let output_of_cast = ksk.cast(&cipher);
+                assert_eq!(
+                    ck2.decrypt_message_and_carry(&output_of_cast),
+                    msg % full_out,
+                    "Invalid cast of {msg} from {:?} with {expected_adjustment:?}",
+                    src_params.message_modulus,
+                );
+                assert_eq!(output_of_cast.noise_level(), NoiseLevel::NOMINAL);
+                assert!(output_of_cast.degree.get() < full_out as usize);
+
+                // The extra keyswitch can be done without refreshing when no upcast PBS is needed
+                if expected_adjustment == DestinationKeyAdjustment::ExtraKeyswitch
+                    && cast_rshift <= 0
+                {
+                    let not_refreshed = ksk.cast_without_refresh(&cipher);
+                    assert_eq!(
+                        ck2.decrypt_message_and_carry(&not_refreshed),
+                        msg % full_out
+                    );
+                    assert_eq!(not_refreshed.noise_level(), NoiseLevel::UNKNOWN);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn gen_multi_keys_test_key_accessors_ci_run_filter() {
+    let ksk_params = PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS;
+
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        ksk_params,
+    ));
+    let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+    let ksk = keys.key_switching_key();
+
+    // KS_PBS source ciphertexts are under the big key, the casting key outputs under the big
+    // destination key
+    assert_eq!(
+        ksk.input_key_lwe_dimension(),
+        ck1.parameters
+            .glwe_dimension()
+            .to_equivalent_lwe_dimension(ck1.parameters.polynomial_size())
+    );
+    assert_eq!(
+        ksk.output_key_lwe_dimension(),
+        ck2.parameters
+            .glwe_dimension()
+            .to_equivalent_lwe_dimension(ck2.parameters.polynomial_size())
+    );
+    assert_eq!(ksk.decomposition_base_log(), ksk_params.ks_base_log);
+    assert_eq!(ksk.decomposition_level_count(), ksk_params.ks_level);
+}
+
+#[test]
+fn gen_multi_keys_test_cast_batch_with_different_degrees_ci_run_filter() {
+    for (src_params, dst_params) in [
+        // Same size, the input degree is kept as is
+        (
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        ),
+        // Downcast, the degree is shifted
+        (
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ),
+        // Upcast, the degree comes from the lookup table
+        (
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+            PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        ),
+    ] {
+        let ksk_params = ShortintKeySwitchingParameters::new(
+            dst_params.ks_base_log,
+            dst_params.ks_level,
+            EncryptionKeyChoice::Big,
+        );
+
+        let keys = KEY_CACHE_KSK.get_from_param((src_params, dst_params, ksk_params));
+        let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+        let ksk = keys.key_switching_key();
+
+        let cast_rshift = ksk.key_switching_key_material.cast_rshift;
+        let full_in = (src_params.message_modulus.0 * src_params.carry_modulus.0) as u64;
+        let full_out = (dst_params.message_modulus.0 * dst_params.carry_modulus.0) as u64;
+
+        let mut lowest = ck1.unchecked_encrypt(0);
+        lowest.degree = Degree::new(0);
+        let mut highest = ck1.unchecked_encrypt(full_in - 1);
+        highest.degree = Degree::new(full_in as usize - 1);
+
+        let batch = [lowest, highest];
+
+        let outputs: Vec<_> = batch.par_iter().map(|ct| ksk.cast(ct)).collect();
+
+        for (input, output) in batch.iter().zip(outputs.iter()) {
+            let decrypted = ck2.decrypt_message_and_carry(output);
+            assert_eq!(decrypted, ck1.decrypt_message_and_carry(input) % full_out);
+
+            assert!(output.degree.get() >= decrypted as usize);
+            assert!(output.degree.get() < full_out as usize);
+
+            if cast_rshift == 0 {
+                assert_eq!(output.degree, input.degree);
+            }
+        }
+    }
+}
+
+#[test]
+fn gen_multi_keys_test_view_try_from_raw_parts_ci_run_filter() {
+    let keys = KEY_CACHE_KSK.get_from_param((
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        ShortintKeySwitchingParameters::new(
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.ks_base_log,
+            PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64.ks_level,
+            EncryptionKeyChoice::Big,
+        ),
+    ));
+    let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+    let ksk = keys.key_switching_key();
+
+    let (material, dest_server_key, src_server_key) = ksk.as_view().into_raw_parts();
+    assert_eq!(material.cast_rshift, -2);
+
+    // Parts coming from a valid key
+    let material_from_parts = KeySwitchingKeyMaterialView::from_raw_parts(
+        material.key_switching_key,
+        material.cast_rshift,
+        material.destination_key,
+    );
+    let view = KeySwitchingKeyView::try_from_raw_parts(
+        material_from_parts,
+        dest_server_key,
+        src_server_key,
+    )
+    .unwrap();
+    assert_eq!(view, ksk.as_view());
+
+    let cipher = ck1.encrypt(1);
+    assert_eq!(ck2.decrypt(&view.cast(&cipher)), 1);
+
+    // Inconsistent cast_rshift
+    let wrong_shift = KeySwitchingKeyMaterialView::from_raw_parts(
+        material.key_switching_key,
+        0,
+        material.destination_key,
+    );
+    let err = KeySwitchingKeyView::try_from_raw_parts(wrong_shift, dest_server_key, src_server_key)
+        .unwrap_err();
+    assert!(err.to_string().contains("cast_rshift"));
+
+    // A downcast requires the source server key
+    assert!(KeySwitchingKeyView::try_from_raw_parts(material, dest_server_key, None).is_err());
+
+    // Wrong destination key choice for the keyswitch key output dimension
+    let wrong_destination = KeySwitchingKeyMaterialView::from_raw_parts(
+        material.key_switching_key,
+        material.cast_rshift,
+        EncryptionKeyChoice::Small,
+    );
+    assert!(KeySwitchingKeyView::try_from_raw_parts(
+        wrong_destination,
+        dest_server_key,
+        src_server_key
+    )
+    .is_err());
+}
+
+#[test]
+fn gen_multi_keys_test_estimate_output_variance_ci_run_filter() {
+    // Same tolerance as the core_crypto noise tests
+    const RELATIVE_TOLERANCE: f64 = 0.0625;
+    // The relative standard deviation of the measured variance is about sqrt(2 / NB_TESTS), i.e.
+    // ~1.4%, well within the tolerance
+    const NB_TESTS: usize = 10_000;
+
+    // The GAUSSIAN_2M64 parameters use the minimal noise for 132 bits of security the noise
+    // formulas are valid for
+    let src_params = PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64;
+    let dst_params = PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64;
+
+    let keys = KEY_CACHE_KSK.get_from_param((
+        src_params,
+        dst_params,
+        ShortintKeySwitchingParameters::new(
+            dst_params.ks_base_log,
+            dst_params.ks_level,
+            EncryptionKeyChoice::Big,
+        ),
+    ));
+    let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+    let ksk = keys.key_switching_key();
+
+    // The downcast refreshes the input on the source key first, the input noise does not matter
+    assert_eq!(ksk.key_switching_key_material.cast_rshift, -2);
+    let expected_variance = ksk.estimate_output_variance(Variance(0.0));
+    assert_eq!(
+        expected_variance,
+        ksk.estimate_output_variance(Variance(2.0f64.powi(-40)))
+    );
+    assert!(ksk.estimate_refreshed_output_variance().0 > 0.0);
+
+    let full_out = (dst_params.message_modulus.0 * dst_params.carry_modulus.0) as u64;
+    // Padding bit
+    let delta = (1u64 << 63) / full_out;
+
+    let noise_samples: Vec<f64> = (0..NB_TESTS)
+        .into_par_iter()
+        .map(|i| {
+            let msg = i as u64 % full_out;
+
+            let cipher = ck1.encrypt(msg);
+            let output_of_cast = ksk.cast_without_refresh(&cipher);
+
+            let decrypted = decrypt_lwe_ciphertext(&ck2.large_lwe_secret_key(), &output_of_cast.ct);
+
+            torus_modular_diff(
+                msg * delta,
+                decrypted.0,
+                output_of_cast.ct.ciphertext_modulus(),
+            )
+        })
+        .collect();
+
+    let measured_variance = variance(&noise_samples);
+
+    println!("measured_variance={measured_variance:?}");
+    println!("expected_variance={expected_variance:?}");
+
+    // The estimate must be close in both directions, an overestimate would hide a term counted
+    // twice
+    let var_abs_diff = (measured_variance.0 - expected_variance.0).abs();
+    let tolerance_threshold = RELATIVE_TOLERANCE * expected_variance.0;
+
+    assert!(
+        var_abs_diff < tolerance_threshold,
+        "Absolute difference for variance: {var_abs_diff}, \
+        tolerance threshold: {tolerance_threshold}, \
+        got variance: {measured_variance:?}, \
+        expected variance: {expected_variance:?}"
+    );
+}
+
+#[test]
+fn gen_multi_keys_test_unpack_and_cast_ci_run_filter() {
+    let src_params = PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
+
+    for dst_params in [
+        // Downcast, the left shift is merged in the decompression PBS
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+        // Same size, decompression then cast
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_GAUSSIAN_2M64,
+    ] {
+        let ksk_params = ShortintKeySwitchingParameters::new(
+            dst_params.ks_base_log,
+            dst_params.ks_level,
+            EncryptionKeyChoice::Big,
+        );
+
+        let keys = KEY_CACHE_KSK.get_from_param((src_params, dst_params, ksk_params));
+        let (ck1, ck2) = (keys.client_key_1(), keys.client_key_2());
+        let ksk = keys.key_switching_key();
+
+        let private_compression_key =
+            ck1.new_compression_private_key(COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+        let (compression_key, decompression_key) =
+            ck1.new_compression_decompression_keys(&private_compression_key);
+
+        let msg_modulus = src_params.message_modulus.0 as u64;
+        let full_out = (dst_params.message_modulus.0 * dst_params.carry_modulus.0) as u64;
+
+        let cts: Vec<_> = (0..msg_modulus).map(|msg| ck1.encrypt(msg)).collect();
+        let packed = compression_key.compress_ciphertexts_into_list(&cts);
+
+        let lookup_tables = ksk.generate_cast_lookup_tables();
+
+        for msg in 0..msg_modulus {
+            let index = msg as usize;
+
+            let output_of_cast = ksk
+                .unpack_and_cast_with_lookup_tables(
+                    &packed,
+                    index,
+                    &decompression_key,
+                    &lookup_tables,
+                )
+                .unwrap();
+
+            let unpacked = decompression_key.unpack(&packed, index).unwrap();
+            let expected = ksk.cast_with_lookup_tables(&unpacked, &lookup_tables);
+
+            assert_eq!(
+                ck2.decrypt_message_and_carry(&output_of_cast),
+                msg % full_out
+            );
+            assert_eq!(
+                ck2.decrypt_message_and_carry(&output_of_cast),
+                ck2.decrypt_message_and_carry(&expected)
+            );
+            assert_eq!(output_of_cast.degree, expected.degree);
+            assert_eq!(output_of_cast.noise_level(), NoiseLevel::NOMINAL);
+        }
+
+        assert!(ksk
+            .unpack_and_cast_with_lookup_tables(
+                &packed,
+                msg_modulus as usize,
+                &decompression_key,
+                &lookup_tables,
+            )
+            .is_none());
+    }
+}
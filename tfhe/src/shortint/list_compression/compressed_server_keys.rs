@@ -16,7 +16,7 @@ use crate::shortint::backward_compatibility::list_compression::{
 use crate::shortint::client_key::ClientKey;
 use crate::shortint::engine::ShortintEngine;
 use crate::shortint::server_key::{PBSConformanceParameters, ShortintBootstrappingKey};
-use crate::shortint::{ClassicPBSParameters, EncryptionKeyChoice, PBSParameters};
+use crate::shortint::{ClassicPBSParameters, PBSParameters};
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use tfhe_versionable::Versionize;
@@ -90,11 +90,9 @@ impl ClientKey {
 
         let params = &private_compression_key.params;
 
-        assert_eq!(
-            cks_params.encryption_key_choice,
-            EncryptionKeyChoice::Big,
-            "Compression is only compatible with ciphertext in post PBS dimension"
-        );
+        params
+            .validate_against(cks_params)
+            .unwrap_or_else(|err| panic!("Invalid compression parameters: {err}"));
 
         let packing_key_switching_key = ShortintEngine::with_thread_local_mut(|engine| {
             allocate_and_generate_new_seeded_lwe_packing_keyswitch_key(
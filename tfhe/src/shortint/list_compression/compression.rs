@@ -5,15 +5,54 @@ use crate::core_crypto::prelude::{
     par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext, CiphertextCount, GlweCiphertext,
     LweCiphertext, LweCiphertextCount, LweCiphertextList, MonomialDegree,
 };
-use crate::shortint::ciphertext::CompressedCiphertextList;
+use crate::safe_serialization::{safe_deserialize, safe_serialize};
+use crate::shortint::ciphertext::{CompressedCiphertextList, Degree};
 use crate::shortint::engine::ShortintEngine;
 use crate::shortint::parameters::NoiseLevel;
 use crate::shortint::server_key::{
     apply_programmable_bootstrap, generate_lookup_table, unchecked_scalar_mul_assign,
 };
-use crate::shortint::{Ciphertext, CiphertextModulus};
+use crate::shortint::{
+    CarryModulus, Ciphertext, CiphertextModulus, ClientKey, MessageModulus, PBSOrder,
+};
 use rayon::iter::ParallelIterator;
 use rayon::slice::ParallelSlice;
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+
+/// Number of ciphertexts compressed and decompressed by [`CompressionKey::self_test`].
+const SELF_TEST_CIPHERTEXT_COUNT: usize = 16;
+
+/// Error returned by [`CompressionKey::self_test`] when a compression and decompression key pair
+/// does not round trip.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompressionError {
+    IncorrectDecompression {
+        index: usize,
+        expected: u64,
+        decrypted: u64,
+    },
+}
+
+impl Display for CompressionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IncorrectDecompression {
+                index,
+                expected,
+                decrypted,
+            } => {
+                write!(
+                    f,
+                    "Ciphertext {index} decrypted to {decrypted} after compression and \
+                    decompression instead of {expected}, the keys do not match"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompressionError {}
 
 impl CompressionKey {
     pub fn compress_ciphertexts_into_list(
@@ -24,90 +63,26 @@ impl CompressionKey {
 
         let lwe_pksk = &self.packing_key_switching_key;
 
-        let polynomial_size = lwe_pksk.output_polynomial_size();
         let ciphertext_modulus = lwe_pksk.ciphertext_modulus();
-        let glwe_size = lwe_pksk.output_glwe_size();
-        let lwe_size = lwe_pksk.input_key_lwe_dimension().to_lwe_size();
 
         let lwe_per_glwe = self.lwe_per_glwe;
 
-        assert!(
-            lwe_per_glwe.0 <= polynomial_size.0,
-            "Cannot pack more than polynomial_size(={}) elements per glwe, {} requested",
-            polynomial_size.0,
-            lwe_per_glwe.0,
-        );
-
         let first_ct = &ciphertexts[0];
 
         let message_modulus = first_ct.message_modulus;
         let carry_modulus = first_ct.carry_modulus;
         let pbs_order = first_ct.pbs_order;
 
-        assert!(
-            message_modulus.0 <= carry_modulus.0,
-            "GLWE packing is implemented with messages in carries, so carry_modulus (={}) can't be bigger than message_modulus(={})",
-            carry_modulus.0,
-            message_modulus.0 ,
-        );
+        self.check_compressible_moduli(message_modulus, carry_modulus);
 
         let glwe_ct_list: Vec<_> = ciphertexts
             .par_chunks(lwe_per_glwe.0)
             .map(|ct_list| {
-                let mut list: Vec<_> = vec![];
-
-                for ct in ct_list {
-                    assert!(
-                        ct.noise_level() == NoiseLevel::NOMINAL,
-                        "Ciphertexts must have a nominal (post PBS) noise to be compressed"
-                    );
-
-                    assert!(
-                        ct.carry_is_empty(),
-                        "Ciphertexts must have empty carries to be compressed"
-                    );
-
-                    assert_eq!(
-                        lwe_size,
-                        ct.ct.lwe_size(),
-                        "All ciphertexts do not have the same lwe size as the packing keyswitch key"
-                    );
-
-                    assert_eq!(
-                        message_modulus, ct.message_modulus,
-                        "All ciphertexts do not have the same message modulus"
-                    );
-                    assert_eq!(
-                        carry_modulus, ct.carry_modulus,
-                        "All ciphertexts do not have the same carry modulus"
-                    );
-                    assert_eq!(
-                        pbs_order, ct.pbs_order,
-                        "All ciphertexts do not have the same pbs order"
-                    );
-
-                    let mut ct = ct.clone();
-
-                    unchecked_scalar_mul_assign(&mut ct, message_modulus.0 as u8);
-
-                    list.extend(ct.ct.as_ref());
-                }
-
-                let list = LweCiphertextList::from_container(list, lwe_size, ciphertext_modulus);
-
-                let bodies_count = LweCiphertextCount(ct_list.len());
-
-                let mut out =
-                    GlweCiphertext::new(0, glwe_size, polynomial_size, ciphertext_modulus);
-
-                par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext(
-                    lwe_pksk, &list, &mut out,
-                );
-
-                CompressedModulusSwitchedGlweCiphertext::compress(
-                    &out,
-                    self.storage_log_modulus,
-                    bodies_count,
+                self.compress_ciphertexts_into_glwe(
+                    ct_list,
+                    message_modulus,
+                    carry_modulus,
+                    pbs_order,
                 )
             })
             .collect();
@@ -122,23 +97,453 @@ impl CompressionKey {
             ciphertext_modulus,
         }
     }
+
+    /// Create a builder compressing ciphertexts as they are pushed, see
+    /// [`CompressedCiphertextListStreamBuilder`].
+    pub fn stream_builder(&self) -> CompressedCiphertextListStreamBuilder<'_> {
+        CompressedCiphertextListStreamBuilder {
+            pending: PendingCiphertexts::new(self),
+            modulus_switched_glwe_ciphertext_list: Vec::new(),
+        }
+    }
+
+    /// Create a builder compressing ciphertexts as they are pushed and serializing each full GLWE
+    /// in `writer` with [`safe_serialize`], see [`CompressedCiphertextListStreamWriter`].
+    pub fn stream_builder_into<W: Write>(
+        &self,
+        writer: W,
+        serialized_size_limit: u64,
+    ) -> CompressedCiphertextListStreamWriter<'_, W> {
+        CompressedCiphertextListStreamWriter {
+            pending: PendingCiphertexts::new(self),
+            writer,
+            serialized_size_limit,
+        }
+    }
+
+    /// Compress a few known values encrypted under `client_key` and check they decrypt correctly
+    /// once decompressed with `decompression_key`.
+    ///
+    /// This is meant to be run once after key generation to catch a key pair that was not
+    /// generated from the same [`CompressionPrivateKeys`](super::CompressionPrivateKeys) and
+    /// client key, or parameters too noisy for the compression, before any actual data goes
+    /// through it. As this uses a few PBS it should not be called on every compression.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::shortint::gen_keys;
+    /// use tfhe::shortint::parameters::list_compression::COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
+    /// use tfhe::shortint::parameters::PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
+    ///
+    /// let (cks, _sks) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+    ///
+    /// let private_compression_key =
+    ///     cks.new_compression_private_key(COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+    ///
+    /// let (compression_key, decompression_key) =
+    ///     cks.new_compression_decompression_keys(&private_compression_key);
+    ///
+    /// compression_key
+    ///     .self_test(&decompression_key, &cks)
+    ///     .unwrap();
+    /// ```
+    pub fn self_test(
+        &self,
+        decompression_key: &DecompressionKey,
+        client_key: &ClientKey,
+    ) -> Result<(), CompressionError> {
+        let message_modulus = client_key.parameters.message_modulus().0 as u64;
+
+        let count = SELF_TEST_CIPHERTEXT_COUNT.min(self.lwe_per_glwe.0);
+
+        let messages: Vec<u64> = (0..count as u64).map(|i| i % message_modulus).collect();
+
+        let cts: Vec<Ciphertext> = messages
+            .iter()
+            .map(|&message| client_key.encrypt(message))
+            .collect();
+
+        let packed = self.compress_ciphertexts_into_list(&cts);
+
+        for (index, &expected) in messages.iter().enumerate() {
+            let unpacked = decompression_key.unpack(&packed, index).unwrap();
+
+            let decrypted = client_key.decrypt_message_and_carry(&unpacked);
+
+            if decrypted != expected {
+                return Err(CompressionError::IncorrectDecompression {
+                    index,
+                    expected,
+                    decrypted,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_compressible_moduli(
+        &self,
+        message_modulus: MessageModulus,
+        carry_modulus: CarryModulus,
+    ) {
+        let polynomial_size = self.packing_key_switching_key.output_polynomial_size();
+
+        assert!(
+            self.lwe_per_glwe.0 <= polynomial_size.0,
+            "Cannot pack more than polynomial_size(={}) elements per glwe, {} requested",
+            polynomial_size.0,
+            self.lwe_per_glwe.0,
+        );
+
+        assert!(
+            message_modulus.0 <= carry_modulus.0,
+            "GLWE packing is implemented with messages in carries, so carry_modulus (={}) can't be bigger than message_modulus(={})",
+            carry_modulus.0,
+            message_modulus.0 ,
+        );
+    }
+
+    fn compress_ciphertexts_into_glwe(
+        &self,
+        ct_list: &[Ciphertext],
+        message_modulus: MessageModulus,
+        carry_modulus: CarryModulus,
+        pbs_order: PBSOrder,
+    ) -> CompressedModulusSwitchedGlweCiphertext<u64> {
+        let lwe_pksk = &self.packing_key_switching_key;
+
+        let polynomial_size = lwe_pksk.output_polynomial_size();
+        let ciphertext_modulus = lwe_pksk.ciphertext_modulus();
+        let glwe_size = lwe_pksk.output_glwe_size();
+        let lwe_size = lwe_pksk.input_key_lwe_dimension().to_lwe_size();
+
+        let mut list: Vec<_> = vec![];
+
+        for ct in ct_list {
+            assert!(
+                ct.noise_level() == NoiseLevel::NOMINAL,
+                "Ciphertexts must have a nominal (post PBS) noise to be compressed"
+            );
+
+            assert!(
+                ct.carry_is_empty(),
+                "Ciphertexts must have empty carries to be compressed"
+            );
+
+            assert_eq!(
+                lwe_size,
+                ct.ct.lwe_size(),
+                "All ciphertexts do not have the same lwe size as the packing keyswitch key"
+            );
+
+            assert_eq!(
+                message_modulus, ct.message_modulus,
+                "All ciphertexts do not have the same message modulus"
+            );
+            assert_eq!(
+                carry_modulus, ct.carry_modulus,
+                "All ciphertexts do not have the same carry modulus"
+            );
+            assert_eq!(
+                pbs_order, ct.pbs_order,
+                "All ciphertexts do not have the same pbs order"
+            );
+
+            let mut ct = ct.clone();
+
+            unchecked_scalar_mul_assign(&mut ct, message_modulus.0 as u8);
+
+            list.extend(ct.ct.as_ref());
+        }
+
+        let list = LweCiphertextList::from_container(list, lwe_size, ciphertext_modulus);
+
+        let bodies_count = LweCiphertextCount(ct_list.len());
+
+        let mut out = GlweCiphertext::new(0, glwe_size, polynomial_size, ciphertext_modulus);
+
+        par_keyswitch_lwe_ciphertext_list_and_pack_in_glwe_ciphertext(lwe_pksk, &list, &mut out);
+
+        CompressedModulusSwitchedGlweCiphertext::compress(
+            &out,
+            self.storage_log_modulus,
+            bodies_count,
+        )
+    }
+}
+
+/// Ciphertexts pushed in a stream builder which do not fill a GLWE yet.
+struct PendingCiphertexts<'key> {
+    compression_key: &'key CompressionKey,
+    pending: Vec<Ciphertext>,
+    count: usize,
+    moduli_and_order: Option<(MessageModulus, CarryModulus, PBSOrder)>,
+}
+
+impl<'key> PendingCiphertexts<'key> {
+    fn new(compression_key: &'key CompressionKey) -> Self {
+        Self {
+            compression_key,
+            pending: Vec::with_capacity(compression_key.lwe_per_glwe.0),
+            count: 0,
+            moduli_and_order: None,
+        }
+    }
+
+    /// Returns the compressed GLWE if `ciphertext` filled it.
+    fn push(
+        &mut self,
+        ciphertext: Ciphertext,
+    ) -> Option<CompressedModulusSwitchedGlweCiphertext<u64>> {
+        if self.moduli_and_order.is_none() {
+            self.compression_key
+                .check_compressible_moduli(ciphertext.message_modulus, ciphertext.carry_modulus);
+            self.moduli_and_order = Some((
+                ciphertext.message_modulus,
+                ciphertext.carry_modulus,
+                ciphertext.pbs_order,
+            ));
+        }
+
+        self.pending.push(ciphertext);
+        self.count += 1;
+
+        if self.pending.len() == self.compression_key.lwe_per_glwe.0 {
+            self.flush()
+        } else {
+            None
+        }
+    }
+
+    fn flush(&mut self) -> Option<CompressedModulusSwitchedGlweCiphertext<u64>> {
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        let (message_modulus, carry_modulus, pbs_order) = self.moduli_and_order.unwrap();
+
+        let glwe = self.compression_key.compress_ciphertexts_into_glwe(
+            &self.pending,
+            message_modulus,
+            carry_modulus,
+            pbs_order,
+        );
+
+        self.pending.clear();
+
+        Some(glwe)
+    }
+
+    fn header(&self) -> CompressedCiphertextListHeader {
+        let (message_modulus, carry_modulus, pbs_order) = self
+            .moduli_and_order
+            .expect("Cannot build a CompressedCiphertextList without any ciphertext");
+
+        CompressedCiphertextListHeader {
+            ciphertext_modulus: self
+                .compression_key
+                .packing_key_switching_key
+                .ciphertext_modulus(),
+            message_modulus,
+            carry_modulus,
+            pbs_order,
+            lwe_per_glwe: self.compression_key.lwe_per_glwe,
+            count: CiphertextCount(self.count),
+        }
+    }
+}
+
+/// Everything in a [`CompressedCiphertextList`] but its GLWEs, returned by
+/// [`CompressedCiphertextListStreamWriter::finish`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompressedCiphertextListHeader {
+    pub ciphertext_modulus: CiphertextModulus,
+    pub message_modulus: MessageModulus,
+    pub carry_modulus: CarryModulus,
+    pub pbs_order: PBSOrder,
+    pub lwe_per_glwe: LweCiphertextCount,
+    pub count: CiphertextCount,
+}
+
+impl CompressedCiphertextListHeader {
+    /// Number of GLWEs holding the [`Self::count`] compressed ciphertexts.
+    pub fn glwe_count(&self) -> usize {
+        self.count.0.div_ceil(self.lwe_per_glwe.0)
+    }
+
+    /// Read back the GLWEs written by a [`CompressedCiphertextListStreamWriter`] and rebuild the
+    /// list.
+    ///
+    /// `serialized_size_limit` applies to each GLWE.
+    pub fn deserialize_list<R: Read>(
+        &self,
+        mut reader: R,
+        serialized_size_limit: u64,
+    ) -> Result<CompressedCiphertextList, String> {
+        let modulus_switched_glwe_ciphertext_list = (0..self.glwe_count())
+            .map(|_| safe_deserialize(&mut reader, serialized_size_limit))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.into_list(modulus_switched_glwe_ciphertext_list))
+    }
+
+    fn into_list(
+        self,
+        modulus_switched_glwe_ciphertext_list: Vec<CompressedModulusSwitchedGlweCiphertext<u64>>,
+    ) -> CompressedCiphertextList {
+        let Self {
+            ciphertext_modulus,
+            message_modulus,
+            carry_modulus,
+            pbs_order,
+            lwe_per_glwe,
+            count,
+        } = self;
+
+        CompressedCiphertextList {
+            modulus_switched_glwe_ciphertext_list,
+            ciphertext_modulus,
+            message_modulus,
+            carry_modulus,
+            pbs_order,
+            lwe_per_glwe,
+            count,
+        }
+    }
+}
+
+/// Builder compressing ciphertexts into a [`CompressedCiphertextList`] as they are pushed.
+///
+/// As soon as `lwe_per_glwe` ciphertexts have been pushed they are packed and compressed into a
+/// single GLWE, so at most `lwe_per_glwe` uncompressed ciphertexts are kept in memory at any time.
+/// The compressed GLWEs are all kept until [`Self::finish`], use
+/// [`CompressedCiphertextListStreamWriter`] to write them out as they are produced when the
+/// compressed list itself does not fit in memory.
+///
+/// The list returned by [`Self::finish`] is identical to the one
+/// [`CompressionKey::compress_ciphertexts_into_list`] returns for the same ciphertexts.
+pub struct CompressedCiphertextListStreamBuilder<'key> {
+    pending: PendingCiphertexts<'key>,
+    modulus_switched_glwe_ciphertext_list: Vec<CompressedModulusSwitchedGlweCiphertext<u64>>,
+}
+
+impl<'key> CompressedCiphertextListStreamBuilder<'key> {
+    /// Push a ciphertext at the end of the list, compressing the pending ciphertexts if a GLWE
+    /// is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`CompressionKey::compress_ciphertexts_into_list`].
+    pub fn push(&mut self, ciphertext: Ciphertext) {
+        if let Some(glwe) = self.pending.push(ciphertext) {
+            self.modulus_switched_glwe_ciphertext_list.push(glwe);
+        }
+    }
+
+    /// Number of ciphertexts pushed so far.
+    pub fn len(&self) -> usize {
+        self.pending.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.count == 0
+    }
+
+    /// Compress the remaining ciphertexts and return the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no ciphertext was pushed.
+    pub fn finish(mut self) -> CompressedCiphertextList {
+        if let Some(glwe) = self.pending.flush() {
+            self.modulus_switched_glwe_ciphertext_list.push(glwe);
+        }
+
+        self.pending
+            .header()
+            .into_list(self.modulus_switched_glwe_ciphertext_list)
+    }
+}
+
+/// Builder compressing ciphertexts as they are pushed and serializing each full GLWE in a
+/// [`Write`] with [`safe_serialize`].
+///
+/// Only the pending uncompressed ciphertexts of the current GLWE are kept in memory. The GLWEs are
+/// written one after the other, [`Self::finish`] returns the
+/// [`CompressedCiphertextListHeader`] needed to read them back with
+/// [`CompressedCiphertextListHeader::deserialize_list`].
+pub struct CompressedCiphertextListStreamWriter<'key, W: Write> {
+    pending: PendingCiphertexts<'key>,
+    writer: W,
+    serialized_size_limit: u64,
+}
+
+impl<'key, W: Write> CompressedCiphertextListStreamWriter<'key, W> {
+    /// Push a ciphertext at the end of the list, compressing the pending ciphertexts and writing
+    /// the result if a GLWE is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`CompressionKey::compress_ciphertexts_into_list`].
+    pub fn push(&mut self, ciphertext: Ciphertext) -> bincode::Result<()> {
+        match self.pending.push(ciphertext) {
+            Some(glwe) => safe_serialize(&glwe, &mut self.writer, self.serialized_size_limit),
+            None => Ok(()),
+        }
+    }
+
+    /// Number of ciphertexts pushed so far.
+    pub fn len(&self) -> usize {
+        self.pending.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.count == 0
+    }
+
+    /// Compress and write the remaining ciphertexts, then return the header of the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no ciphertext was pushed.
+    pub fn finish(mut self) -> bincode::Result<CompressedCiphertextListHeader> {
+        if let Some(glwe) = self.pending.flush() {
+            safe_serialize(&glwe, &mut self.writer, self.serialized_size_limit)?;
+        }
+
+        self.writer.flush()?;
+
+        Ok(self.pending.header())
+    }
 }
 
 impl DecompressionKey {
     pub fn unpack(&self, packed: &CompressedCiphertextList, index: usize) -> Option<Ciphertext> {
+        let mod_switched = self.unpack_mod_switched(packed, index)?;
+
+        Some(self.bootstrap_mod_switched(&mod_switched))
+    }
+
+    /// Extract the ciphertext at `index` from the packed list without applying the final PBS.
+    ///
+    /// The returned [`Ciphertext`] is encrypted under the compression GLWE secret key seen as an
+    /// LWE secret key, it still holds its message in the carries (as it was packed) and its noise
+    /// includes the modulus switch noise of the compression. This is meant for noise analysis,
+    /// [`Self::bootstrap_mod_switched`] turns it into the ciphertext returned by
+    /// [`Self::unpack`].
+    ///
+    /// Returns `None` if `index` is out of bounds.
+    pub fn unpack_mod_switched(
+        &self,
+        packed: &CompressedCiphertextList,
+        index: usize,
+    ) -> Option<Ciphertext> {
         if index >= packed.count.0 {
             return None;
         }
 
-        let carry_extract = generate_lookup_table(
-            self.out_glwe_size(),
-            self.out_polynomial_size(),
-            packed.ciphertext_modulus,
-            packed.message_modulus,
-            packed.carry_modulus,
-            |x| x / packed.message_modulus.0 as u64,
-        );
-
         let polynomial_size = packed.modulus_switched_glwe_ciphertext_list[0].polynomial_size();
         let ciphertext_modulus = packed.ciphertext_modulus;
         let glwe_dimension = packed.modulus_switched_glwe_ciphertext_list[0].glwe_dimension();
@@ -163,6 +568,48 @@ impl DecompressionKey {
             monomial_degree,
         );
 
+        // Messages are packed in the carries
+        let max_message = packed.message_modulus.0 - 1;
+
+        Some(Ciphertext::new(
+            intermediate_lwe,
+            Degree::new(max_message * packed.message_modulus.0),
+            NoiseLevel::UNKNOWN,
+            packed.message_modulus,
+            packed.carry_modulus,
+            packed.pbs_order,
+        ))
+    }
+
+    /// Apply the final PBS of the decompression to a ciphertext returned by
+    /// [`Self::unpack_mod_switched`], moving its message from the carries back to the message
+    /// bits under the compute keys.
+    pub fn bootstrap_mod_switched(&self, mod_switched: &Ciphertext) -> Ciphertext {
+        self.bootstrap_mod_switched_with_function(mod_switched, |x| x)
+    }
+
+    /// Same as [`Self::bootstrap_mod_switched`] but `function` is applied to the message in the
+    /// same PBS.
+    pub(crate) fn bootstrap_mod_switched_with_function<F>(
+        &self,
+        mod_switched: &Ciphertext,
+        function: F,
+    ) -> Ciphertext
+    where
+        F: Fn(u64) -> u64,
+    {
+        let ciphertext_modulus = mod_switched.ct.ciphertext_modulus();
+        let message_modulus = mod_switched.message_modulus;
+
+        let carry_extract = generate_lookup_table(
+            self.out_glwe_size(),
+            self.out_polynomial_size(),
+            ciphertext_modulus,
+            message_modulus,
+            mod_switched.carry_modulus,
+            |x| function(x / message_modulus.0 as u64),
+        );
+
         let mut output_br = LweCiphertext::new(
             0,
             self.blind_rotate_key.output_lwe_dimension().to_lwe_size(),
@@ -178,21 +625,21 @@ impl DecompressionKey {
 
             apply_programmable_bootstrap(
                 &self.blind_rotate_key,
-                &intermediate_lwe,
+                &mod_switched.ct,
                 &mut output_br,
                 &carry_extract.acc,
                 buffers,
             );
         });
 
-        Some(Ciphertext::new(
+        Ciphertext::new(
             output_br,
             carry_extract.degree,
             NoiseLevel::NOMINAL,
-            packed.message_modulus,
-            packed.carry_modulus,
-            packed.pbs_order,
-        ))
+            message_modulus,
+            mod_switched.carry_modulus,
+            mod_switched.pbs_order,
+        )
     }
 }
 
@@ -249,4 +696,115 @@ mod test {
             assert_eq!(f(i as u64), res);
         });
     }
+
+    #[test]
+    fn test_stream_builder_matches_batch_compression() {
+        let (cks, _sks) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+
+        let private_compression_key =
+            cks.new_compression_private_key(COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+
+        let (compression_key, decompression_key) =
+            cks.new_compression_decompression_keys(&private_compression_key);
+
+        let lwe_per_glwe = compression_key.lwe_per_glwe.0;
+
+        // Less than a GLWE, exactly a GLWE and a partially filled last GLWE
+        for number_to_pack in [1, lwe_per_glwe, 2 * lwe_per_glwe + 3] {
+            let cts: Vec<_> = (0..number_to_pack)
+                .map(|i| cks.encrypt(i as u64 % 4))
+                .collect();
+
+            let batch = compression_key.compress_ciphertexts_into_list(&cts);
+
+            let mut builder = compression_key.stream_builder();
+            assert!(builder.is_empty());
+            for ct in cts {
+                builder.push(ct);
+            }
+            assert_eq!(builder.len(), number_to_pack);
+            let streamed = builder.finish();
+
+            assert_eq!(streamed, batch);
+
+            for i in [0, number_to_pack - 1] {
+                let unpacked = decompression_key.unpack(&streamed, i).unwrap();
+                assert_eq!(cks.decrypt_message_and_carry(&unpacked), i as u64 % 4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_writer_matches_batch_compression() {
+        let (cks, _sks) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+
+        let private_compression_key =
+            cks.new_compression_private_key(COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+
+        let (compression_key, decompression_key) =
+            cks.new_compression_decompression_keys(&private_compression_key);
+
+        let lwe_per_glwe = compression_key.lwe_per_glwe.0;
+        let serialized_size_limit = 1 << 30;
+
+        // Less than a GLWE, exactly a GLWE and a partially filled last GLWE
+        for number_to_pack in [1, lwe_per_glwe, 2 * lwe_per_glwe + 3] {
+            let cts: Vec<_> = (0..number_to_pack)
+                .map(|i| cks.encrypt(i as u64 % 4))
+                .collect();
+
+            let batch = compression_key.compress_ciphertexts_into_list(&cts);
+
+            let mut serialized = Vec::new();
+            let mut writer =
+                compression_key.stream_builder_into(&mut serialized, serialized_size_limit);
+            assert!(writer.is_empty());
+            for ct in cts {
+                writer.push(ct).unwrap();
+            }
+            assert_eq!(writer.len(), number_to_pack);
+            let header = writer.finish().unwrap();
+
+            assert_eq!(header.count.0, number_to_pack);
+            assert_eq!(
+                header.glwe_count(),
+                batch.modulus_switched_glwe_ciphertext_list.len()
+            );
+
+            let streamed = header
+                .deserialize_list(serialized.as_slice(), serialized_size_limit)
+                .unwrap();
+
+            assert_eq!(streamed, batch);
+
+            for i in [0, number_to_pack - 1] {
+                let unpacked = decompression_key.unpack(&streamed, i).unwrap();
+                assert_eq!(cks.decrypt_message_and_carry(&unpacked), i as u64 % 4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_self_test() {
+        let (cks, _sks) = gen_keys(PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+
+        let private_compression_key =
+            cks.new_compression_private_key(COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+        let other_private_compression_key =
+            cks.new_compression_private_key(COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64);
+
+        let (compression_key, decompression_key) =
+            cks.new_compression_decompression_keys(&private_compression_key);
+        let (_, other_decompression_key) =
+            cks.new_compression_decompression_keys(&other_private_compression_key);
+
+        assert_eq!(compression_key.self_test(&decompression_key, &cks), Ok(()));
+
+        // The decompression key bootstraps from a different post packing key, decrypted values are
+        // random
+        assert!(matches!(
+            compression_key.self_test(&other_decompression_key, &cks),
+            Err(CompressionError::IncorrectDecompression { .. })
+        ));
+    }
 }
@@ -4,5 +4,9 @@ mod private_key;
 mod server_keys;
 
 pub use compressed_server_keys::{CompressedCompressionKey, CompressedDecompressionKey};
+pub use compression::{
+    CompressedCiphertextListHeader, CompressedCiphertextListStreamBuilder,
+    CompressedCiphertextListStreamWriter, CompressionError,
+};
 pub use private_key::CompressionPrivateKeys;
 pub use server_keys::{CompressionConformanceParameters, CompressionKey, DecompressionKey};
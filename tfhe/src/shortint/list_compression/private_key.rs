@@ -7,7 +7,7 @@ use crate::shortint::backward_compatibility::list_compression::CompressionPrivat
 use crate::shortint::client_key::ClientKey;
 use crate::shortint::engine::ShortintEngine;
 use crate::shortint::parameters::list_compression::CompressionParameters;
-use crate::shortint::{ClassicPBSParameters, EncryptionKeyChoice, PBSParameters};
+use crate::shortint::{ClassicPBSParameters, PBSParameters};
 use std::fmt::Debug;
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Versionize)]
@@ -29,11 +29,9 @@ impl ClientKey {
             }
         };
 
-        assert_eq!(
-            cks_params.encryption_key_choice,
-            EncryptionKeyChoice::Big,
-            "Compression is only compatible with ciphertext in post PBS dimension"
-        );
+        params
+            .validate_against(cks_params)
+            .unwrap_or_else(|err| panic!("Invalid compression parameters: {err}"));
 
         let post_packing_ks_key = ShortintEngine::with_thread_local_mut(|engine| {
             allocate_and_generate_new_binary_glwe_secret_key(
@@ -8,7 +8,7 @@ use crate::shortint::client_key::ClientKey;
 use crate::shortint::engine::ShortintEngine;
 use crate::shortint::parameters::{CompressionParameters, PolynomialSize};
 use crate::shortint::server_key::{PBSConformanceParameters, ShortintBootstrappingKey};
-use crate::shortint::{ClassicPBSParameters, EncryptionKeyChoice, PBSParameters};
+use crate::shortint::{ClassicPBSParameters, PBSParameters};
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use tfhe_versionable::Versionize;
@@ -52,11 +52,9 @@ impl ClientKey {
 
         let params = &private_compression_key.params;
 
-        assert_eq!(
-            cks_params.encryption_key_choice,
-            EncryptionKeyChoice::Big,
-            "Compression is only compatible with ciphertext in post PBS dimension"
-        );
+        params
+            .validate_against(cks_params)
+            .unwrap_or_else(|err| panic!("Invalid compression parameters: {err}"));
 
         let packing_key_switching_key = ShortintEngine::with_thread_local_mut(|engine| {
             allocate_and_generate_new_lwe_packing_keyswitch_key(
@@ -70,15 +68,6 @@ impl ClientKey {
             )
         });
 
-        assert!(
-            private_compression_key.params.storage_log_modulus.0
-                <= cks_params
-                    .polynomial_size
-                    .to_blind_rotation_input_modulus_log()
-                    .0,
-            "Compression parameters say to store more bits than useful"
-        );
-
         let glwe_compression_key = CompressionKey {
             packing_key_switching_key,
             lwe_per_glwe: params.lwe_per_glwe,
@@ -2,9 +2,10 @@ pub mod p_fail_2_minus_64;
 
 use crate::shortint::backward_compatibility::parameters::key_switching::ShortintKeySwitchingParametersVersions;
 use crate::shortint::parameters::{
-    DecompositionBaseLog, DecompositionLevelCount, EncryptionKeyChoice,
+    DecompositionBaseLog, DecompositionLevelCount, EncryptionKeyChoice, LweDimension,
 };
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 use tfhe_versionable::Versionize;
 
 /// A set of cryptographic parameters for homomorphic Shortint key switching.
@@ -36,4 +37,196 @@ impl ShortintKeySwitchingParameters {
             destination_key,
         }
     }
+
+    /// Check that the decomposition of these parameters is precise enough to keyswitch
+    /// ciphertexts encrypted under a key of dimension `input_lwe_dimension`, for messages with the
+    /// given input and output full message moduli (message modulus times carry modulus).
+    ///
+    /// The keyswitch only keeps the `ks_base_log * ks_level` most significant bits of each input
+    /// mask element, the rounding error on the dropped bits is multiplied by the input secret key
+    /// and added to the ciphertext. This checks that this rounding error stays below half the
+    /// encoding step of the biggest of the two moduli with a failure probability of about
+    /// $2^{-64}$, assuming a native 64 bits ciphertext modulus and a binary input secret key.
+    ///
+    /// The encryption noise of the key is not taken into account as it depends on the output
+    /// parameters, so this check is necessary but not sufficient: parameters passing it are not
+    /// guaranteed to be correct, parameters failing it are guaranteed to be too noisy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::shortint::parameters::key_switching::p_fail_2_minus_64::ks_pbs::PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS;
+    /// use tfhe::shortint::parameters::{
+    ///     DecompositionBaseLog, DecompositionLevelCount, EncryptionKeyChoice,
+    ///     ShortintKeySwitchingParameters, PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64,
+    /// };
+    ///
+    /// let input_params = PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64;
+    /// // The casting key keyswitches ciphertexts under the big key of the input parameters
+    /// let input_lwe_dimension = input_params
+    ///     .glwe_dimension
+    ///     .to_equivalent_lwe_dimension(input_params.polynomial_size);
+    ///
+    /// assert!(PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS
+    ///     .is_suitable_for(input_lwe_dimension, 4, 16)
+    ///     .is_ok());
+    ///
+    /// let too_coarse = ShortintKeySwitchingParameters::new(
+    ///     DecompositionBaseLog(1),
+    ///     DecompositionLevelCount(2),
+    ///     EncryptionKeyChoice::Big,
+    /// );
+    /// assert!(too_coarse
+    ///     .is_suitable_for(input_lwe_dimension, 4, 16)
+    ///     .is_err());
+    /// ```
+    pub fn is_suitable_for(
+        &self,
+        input_lwe_dimension: LweDimension,
+        input_full_message_modulus: usize,
+        output_full_message_modulus: usize,
+    ) -> Result<(), KeySwitchingParamError> {
+        let decomposition_bits = self.ks_base_log.0 * self.ks_level.0;
+
+        if decomposition_bits > u64::BITS as usize {
+            return Err(KeySwitchingParamError::DecompositionTooLarge { decomposition_bits });
+        }
+
+        // Number of standard deviations for a gaussian failure probability of about 2^-64
+        const STD_DEV_COUNT_FOR_2_M64: f64 = 9.2;
+
+        // The rounding error on each input mask element is uniform in
+        // [-2^(63 - bits), 2^(63 - bits)), i.e. has a variance of 2^(2 * (64 - bits)) / 12, it is
+        // multiplied by a binary key element which is 1 half of the time.
+        let rounding_std_dev_log2 = 0.5 * (input_lwe_dimension.0 as f64 / 24.0).log2()
+            + (u64::BITS as usize - decomposition_bits) as f64;
+
+        // Half of the encoding step with a padding bit, 2^63 / full_message_modulus / 2
+        let max_full_message_modulus = input_full_message_modulus.max(output_full_message_modulus);
+        let max_std_dev_log2 = (u64::BITS - 2) as f64
+            - (max_full_message_modulus as f64).log2()
+            - STD_DEV_COUNT_FOR_2_M64.log2();
+
+        if rounding_std_dev_log2 > max_std_dev_log2 {
+            return Err(KeySwitchingParamError::DecompositionTooCoarse {
+                decomposition_bits,
+                rounding_std_dev_log2,
+                max_std_dev_log2,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Error returned by [`ShortintKeySwitchingParameters::is_suitable_for`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeySwitchingParamError {
+    /// The decomposition keeps more bits than the 64 bits of the ciphertext modulus.
+    DecompositionTooLarge { decomposition_bits: usize },
+    /// The rounding error of the decomposition is too big for the message moduli.
+    DecompositionTooCoarse {
+        decomposition_bits: usize,
+        rounding_std_dev_log2: f64,
+        max_std_dev_log2: f64,
+    },
+}
+
+impl Display for KeySwitchingParamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DecompositionTooLarge { decomposition_bits } => write!(
+                f,
+                "The keyswitch decomposition keeps {decomposition_bits} bits, \
+                which is more than the 64 bits of the ciphertext modulus"
+            ),
+            Self::DecompositionTooCoarse {
+                decomposition_bits,
+                rounding_std_dev_log2,
+                max_std_dev_log2,
+            } => write!(
+                f,
+                "The keyswitch decomposition keeps only {decomposition_bits} bits, \
+                its rounding error has a standard deviation of 2^{rounding_std_dev_log2:.2} \
+                which is above the maximum of 2^{max_std_dev_log2:.2} for these message moduli"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeySwitchingParamError {}
+
+#[cfg(test)]
+mod test {
+    use super::p_fail_2_minus_64::ks_pbs::PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS;
+    use super::*;
+    use crate::shortint::parameters::{
+        PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64, PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
+        PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64,
+    };
+
+    #[test]
+    fn test_keyswitching_parameters_suitability() {
+        let input_params = PARAM_MESSAGE_1_CARRY_1_KS_PBS_GAUSSIAN_2M64;
+        let input_lwe_dimension = input_params
+            .glwe_dimension
+            .to_equivalent_lwe_dimension(input_params.polynomial_size);
+
+        // Known good casting parameters
+        assert_eq!(
+            PARAM_KEYSWITCH_1_1_KS_PBS_TO_2_2_KS_PBS.is_suitable_for(input_lwe_dimension, 4, 16),
+            Ok(())
+        );
+
+        // The keyswitch parameters of optimized compute parameters are suitable for their own
+        // ciphertexts
+        for (params, full_message_modulus) in [
+            (PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64, 16),
+            (PARAM_MESSAGE_3_CARRY_3_KS_PBS_GAUSSIAN_2M64, 64),
+        ] {
+            let ksk_params = ShortintKeySwitchingParameters::new(
+                params.ks_base_log,
+                params.ks_level,
+                params.encryption_key_choice,
+            );
+            let big_lwe_dimension = params
+                .glwe_dimension
+                .to_equivalent_lwe_dimension(params.polynomial_size);
+
+            assert_eq!(
+                ksk_params.is_suitable_for(
+                    big_lwe_dimension,
+                    full_message_modulus,
+                    full_message_modulus
+                ),
+                Ok(())
+            );
+        }
+
+        // Known bad: a 2 bits decomposition cannot keyswitch 4 bits messages
+        let too_coarse = ShortintKeySwitchingParameters::new(
+            DecompositionBaseLog(1),
+            DecompositionLevelCount(2),
+            EncryptionKeyChoice::Big,
+        );
+        assert!(matches!(
+            too_coarse.is_suitable_for(input_lwe_dimension, 4, 16),
+            Err(KeySwitchingParamError::DecompositionTooCoarse {
+                decomposition_bits: 2,
+                ..
+            })
+        ));
+
+        let too_large = ShortintKeySwitchingParameters::new(
+            DecompositionBaseLog(10),
+            DecompositionLevelCount(7),
+            EncryptionKeyChoice::Big,
+        );
+        assert_eq!(
+            too_large.is_suitable_for(input_lwe_dimension, 4, 16),
+            Err(KeySwitchingParamError::DecompositionTooLarge {
+                decomposition_bits: 70
+            })
+        );
+    }
 }
@@ -3,10 +3,10 @@ use tfhe_versionable::Versionize;
 use crate::core_crypto::prelude::{CiphertextModulusLog, LweCiphertextCount};
 use crate::shortint::backward_compatibility::parameters::list_compression::CompressionParametersVersions;
 use crate::shortint::parameters::{
-    DecompositionBaseLog, DecompositionLevelCount, DynamicDistribution, GlweDimension,
-    PolynomialSize,
+    CarryModulus, ClassicPBSParameters, DecompositionBaseLog, DecompositionLevelCount,
+    DynamicDistribution, EncryptionKeyChoice, GlweDimension, MessageModulus, PolynomialSize,
 };
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 
 #[derive(Copy, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, Versionize)]
 #[versionize(CompressionParametersVersions)]
@@ -22,6 +22,186 @@ pub struct CompressionParameters {
     pub packing_ks_key_noise_distribution: DynamicDistribution<u64>,
 }
 
+/// Error returned when [`CompressionParameters`] cannot be used with a set of compute parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompressionParamError {
+    NotPostPbsDimension,
+    CarryModulusTooSmall {
+        message_modulus: MessageModulus,
+        carry_modulus: CarryModulus,
+    },
+    TooManyLwePerGlwe {
+        lwe_per_glwe: LweCiphertextCount,
+        packing_ks_polynomial_size: PolynomialSize,
+    },
+    StorageLogModulusTooBig {
+        storage_log_modulus: CiphertextModulusLog,
+        max_storage_log_modulus: CiphertextModulusLog,
+    },
+}
+
+impl Display for CompressionParamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotPostPbsDimension => {
+                write!(
+                    f,
+                    "Compression is only compatible with ciphertext in post PBS dimension"
+                )
+            }
+            Self::CarryModulusTooSmall {
+                message_modulus,
+                carry_modulus,
+            } => {
+                write!(
+                    f,
+                    "GLWE packing is implemented with messages in carries, \
+                    so carry_modulus (={}) can't be smaller than message_modulus (={})",
+                    carry_modulus.0, message_modulus.0,
+                )
+            }
+            Self::TooManyLwePerGlwe {
+                lwe_per_glwe,
+                packing_ks_polynomial_size,
+            } => {
+                write!(
+                    f,
+                    "Cannot pack more than packing_ks_polynomial_size (={}) elements per glwe, \
+                    {} requested",
+                    packing_ks_polynomial_size.0, lwe_per_glwe.0,
+                )
+            }
+            Self::StorageLogModulusTooBig {
+                storage_log_modulus,
+                max_storage_log_modulus,
+            } => {
+                write!(
+                    f,
+                    "The storage_log_modulus (={}) should not exceed {}, \
+                    the stored bits would not be used by the decompression",
+                    storage_log_modulus.0, max_storage_log_modulus.0,
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompressionParamError {}
+
+impl CompressionParameters {
+    /// Return the decomposition base log and level count of the LWE packing keyswitch key.
+    ///
+    /// The packing keyswitch key is generated from the big (post PBS) LWE secret key of the
+    /// compute parameters to a GLWE secret key of dimension `packing_ks_glwe_dimension` and
+    /// polynomial size `packing_ks_polynomial_size`, using this decomposition. This plays the same
+    /// role as the `ks_base_log` and `ks_level` of a
+    /// [`ShortintKeySwitchingParameters`](super::ShortintKeySwitchingParameters) with a `Big`
+    /// source key, the output being a GLWE instead of an LWE.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::shortint::parameters::COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
+    ///
+    /// let (base_log, level) =
+    ///     COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64.packing_keyswitch_params();
+    ///
+    /// assert_eq!(
+    ///     base_log,
+    ///     COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64.packing_ks_base_log
+    /// );
+    /// assert_eq!(
+    ///     level,
+    ///     COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64.packing_ks_level
+    /// );
+    /// ```
+    pub fn packing_keyswitch_params(&self) -> (DecompositionBaseLog, DecompositionLevelCount) {
+        (self.packing_ks_base_log, self.packing_ks_level)
+    }
+
+    /// Return how many values made of `num_blocks_per_message` blocks can be packed in a single
+    /// GLWE before a new GLWE is needed, i.e. the number of whole messages fitting in
+    /// `lwe_per_glwe` slots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_blocks_per_message` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tfhe::shortint::parameters::COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
+    ///
+    /// let params = COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
+    ///
+    /// // 256 LWEs per GLWE: 64 values of 4 blocks fit in a GLWE
+    /// assert_eq!(params.lwe_per_glwe.0, 256);
+    /// assert_eq!(params.max_messages(4), 64);
+    /// ```
+    pub fn max_messages(&self, num_blocks_per_message: usize) -> usize {
+        assert_ne!(
+            num_blocks_per_message, 0,
+            "A message must be made of at least one block"
+        );
+
+        self.lwe_per_glwe.0 / num_blocks_per_message
+    }
+
+    /// Check that these parameters can compress ciphertexts of the given compute parameters.
+    ///
+    /// The stored modulus cannot be bigger than the modulus used as input of the decompression
+    /// blind rotation (`2 * polynomial_size` of the compute parameters), nor than the compute
+    /// ciphertext modulus, and the packed GLWEs must be able to hold `lwe_per_glwe` ciphertexts.
+    pub fn validate_against(
+        &self,
+        compute_params: ClassicPBSParameters,
+    ) -> Result<(), CompressionParamError> {
+        if compute_params.encryption_key_choice != EncryptionKeyChoice::Big {
+            return Err(CompressionParamError::NotPostPbsDimension);
+        }
+
+        if compute_params.message_modulus.0 > compute_params.carry_modulus.0 {
+            return Err(CompressionParamError::CarryModulusTooSmall {
+                message_modulus: compute_params.message_modulus,
+                carry_modulus: compute_params.carry_modulus,
+            });
+        }
+
+        if self.lwe_per_glwe.0 > self.packing_ks_polynomial_size.0 {
+            return Err(CompressionParamError::TooManyLwePerGlwe {
+                lwe_per_glwe: self.lwe_per_glwe,
+                packing_ks_polynomial_size: self.packing_ks_polynomial_size,
+            });
+        }
+
+        let ciphertext_modulus_log = if compute_params.ciphertext_modulus.is_native_modulus() {
+            u64::BITS as usize
+        } else {
+            compute_params
+                .ciphertext_modulus
+                .get_custom_modulus()
+                .ilog2() as usize
+        };
+
+        let max_storage_log_modulus = CiphertextModulusLog(
+            compute_params
+                .polynomial_size
+                .to_blind_rotation_input_modulus_log()
+                .0
+                .min(ciphertext_modulus_log),
+        );
+
+        if self.storage_log_modulus.0 > max_storage_log_modulus.0 {
+            return Err(CompressionParamError::StorageLogModulusTooBig {
+                storage_log_modulus: self.storage_log_modulus,
+                max_storage_log_modulus,
+            });
+        }
+
+        Ok(())
+    }
+}
+
 pub const COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64: CompressionParameters =
     CompressionParameters {
         br_level: DecompositionLevelCount(1),
@@ -34,3 +214,74 @@ pub const COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64: CompressionParamete
         storage_log_modulus: CiphertextModulusLog(12),
         packing_ks_key_noise_distribution: DynamicDistribution::new_t_uniform(42),
     };
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shortint::parameters::{
+        PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64, PARAM_MESSAGE_2_CARRY_2_PBS_KS_TUNIFORM_2M64,
+    };
+
+    #[test]
+    fn test_compression_parameters_validation() {
+        let compute_params = PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
+
+        assert_eq!(
+            COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64.validate_against(compute_params),
+            Ok(())
+        );
+
+        let max_storage_log_modulus = compute_params
+            .polynomial_size
+            .to_blind_rotation_input_modulus_log();
+
+        let params = CompressionParameters {
+            storage_log_modulus: CiphertextModulusLog(max_storage_log_modulus.0 + 1),
+            ..COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64
+        };
+        assert_eq!(
+            params.validate_against(compute_params),
+            Err(CompressionParamError::StorageLogModulusTooBig {
+                storage_log_modulus: params.storage_log_modulus,
+                max_storage_log_modulus,
+            })
+        );
+
+        let params = CompressionParameters {
+            lwe_per_glwe: LweCiphertextCount(
+                COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64
+                    .packing_ks_polynomial_size
+                    .0
+                    + 1,
+            ),
+            ..COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64
+        };
+        assert!(matches!(
+            params.validate_against(compute_params),
+            Err(CompressionParamError::TooManyLwePerGlwe { .. })
+        ));
+
+        assert_eq!(
+            COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64
+                .validate_against(PARAM_MESSAGE_2_CARRY_2_PBS_KS_TUNIFORM_2M64),
+            Err(CompressionParamError::NotPostPbsDimension)
+        );
+    }
+
+    #[test]
+    fn test_compression_parameters_max_messages() {
+        let params = COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64;
+
+        assert_eq!(params.max_messages(1), params.lwe_per_glwe.0);
+        assert_eq!(params.max_messages(4), params.lwe_per_glwe.0 / 4);
+
+        // Only whole messages are counted
+        let params = CompressionParameters {
+            lwe_per_glwe: LweCiphertextCount(10),
+            ..COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64
+        };
+        assert_eq!(params.max_messages(1), 10);
+        assert_eq!(params.max_messages(4), 2);
+        assert_eq!(params.max_messages(11), 0);
+    }
+}
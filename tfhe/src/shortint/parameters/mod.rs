@@ -43,7 +43,7 @@ pub use crate::shortint::parameters::classic::gaussian::p_fail_2_minus_64::pbs_k
 pub use crate::shortint::parameters::classic::tuniform::p_fail_2_minus_64::ks_pbs::*;
 pub use crate::shortint::parameters::classic::tuniform::p_fail_2_minus_64::pbs_ks::*;
 pub use crate::shortint::parameters::list_compression::{
-    CompressionParameters, COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
+    CompressionParamError, CompressionParameters, COMP_PARAM_MESSAGE_2_CARRY_2_KS_PBS_TUNIFORM_2M64,
 };
 pub use crate::shortint::parameters::multi_bit::gaussian::p_fail_2_minus_64::ks_pbs::*;
 pub use crate::shortint::parameters::multi_bit::gaussian::p_fail_2_minus_64::ks_pbs_gpu::*;
@@ -54,7 +54,7 @@ pub use compact_public_key_only::{
 };
 #[cfg(tarpaulin)]
 pub use coverage_parameters::*;
-pub use key_switching::ShortintKeySwitchingParameters;
+pub use key_switching::{KeySwitchingParamError, ShortintKeySwitchingParameters};
 pub use multi_bit::MultiBitPBSParameters;
 pub use parameters_wopbs::*;
 